@@ -34,15 +34,32 @@ fn api_args(inputs: Punctuated<FnArg, Comma>) -> Option<Stmt> {
 
     if has_option {
         Some(parse_quote! {
-            let (#names,) = req.args().optional::<(#types,)>(#len)?;
+            let (#names,) = req
+                .args()
+                .optional::<(#types,)>(#len)
+                .map_err(crate::api_manager::ApiError::invalid_args)?;
         })
     } else {
         Some(parse_quote! {
-            let (#names,) = req.args().get::<(#types,)>()?;
+            let (#names,) = req
+                .args()
+                .get::<(#types,)>()
+                .map_err(crate::api_manager::ApiError::invalid_args)?;
         })
     }
 }
 
+/// Turns a function into an API handler for `ApiManager::register_api`.
+///
+/// The function's parameters are decoded from the request arguments
+/// (`422` on mismatch; with any `Option` parameter, missing trailing
+/// arguments become `None`). Inside the body these bindings are available:
+/// - `app`: the `Arc<App>`.
+/// - `req`: the raw `ApiRequest`.
+/// - `target` / `flow`: the event loop target and control flow.
+/// - `ctx`: the `RequestContext` with the request `id`, the `api` name, the
+///   webview `label` and `window_id`, for emitting events correlated with
+///   the call such as `ctx.progress(percent, message)`.
 #[proc_macro_attribute]
 pub fn api(_: TokenStream, raw: TokenStream) -> TokenStream {
     let f = parse_macro_input!(raw as ItemFn);
@@ -57,6 +74,8 @@ pub fn api(_: TokenStream, raw: TokenStream) -> TokenStream {
             target: &crate::utils::FrameWindowTarget,
             flow: &mut tao::event_loop::ControlFlow,
         ) #output {
+            #[allow(unused_variables)]
+            let ctx = crate::api_manager::RequestContext::new(&app, &req);
             #args_stmt
             #(#body)*
         }
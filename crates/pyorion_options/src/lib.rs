@@ -1 +1,3 @@
+pub mod runtime;
+pub mod strict;
 pub mod window;
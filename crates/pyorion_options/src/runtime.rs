@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default time the connection layer waits for a response from the event loop.
+pub const DEFAULT_REQUEST_TIMEOUT_MS: u64 = 10_000;
+
+/// Default permissions of the Unix socket file: owner read/write only.
+pub const DEFAULT_SOCKET_MODE: u32 = 0o600;
+
+/// Default number of failed auth frames before a connection is dropped.
+pub const DEFAULT_MAX_AUTH_ATTEMPTS: u32 = 3;
+
+/// Default number of client connections served at the same time.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 16;
+
+/// Default upper bound for the size of one request frame: 16 MiB.
+pub const DEFAULT_MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+/// Default minimum gap between two `moved` or `resized` events.
+pub const DEFAULT_WINDOW_EVENT_INTERVAL_MS: u64 = 100;
+
+/// Default worker threads of the multi-thread async runtime.
+pub const DEFAULT_WORKER_THREADS: usize = 2;
+
+/// Scheduler of the async runtime serving connections.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RuntimeFlavor {
+    /// A pool of `workerThreads` threads; connections are served in parallel.
+    #[default]
+    MultiThread,
+    /// One background thread runs every task; the smallest footprint when
+    /// the host application already has plenty of threads, but a slow
+    /// request holds up every other connection.
+    CurrentThread,
+}
+
+/// Wire encoding of request and response frames.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Codec {
+    #[default]
+    Json,
+    #[serde(rename = "msgpack")]
+    MsgPack,
+}
+
+impl Codec {
+    pub fn name(self) -> &'static str {
+        match self {
+            Codec::Json => "json",
+            Codec::MsgPack => "msgpack",
+        }
+    }
+}
+
+impl std::str::FromStr for Codec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Codec::Json),
+            "msgpack" => Ok(Codec::MsgPack),
+            other => Err(format!("unknown codec {:?}", other)),
+        }
+    }
+}
+
+/// Line format of the native log output on stderr.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line, for forwarding into Python `logging`.
+    Json,
+}
+
+/// Exponential backoff used when the connection listener has to be rebuilt.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BackoffOptions {
+    /// Delay before the first retry in milliseconds (default `250`).
+    pub initial_delay_ms: Option<u64>,
+    /// Upper bound for a single delay in milliseconds (default `10000`).
+    pub max_delay_ms: Option<u64>,
+    /// Factor applied to the delay after every failed attempt (default `2.0`).
+    pub multiplier: Option<f64>,
+    /// Retries before giving up (default `10`).
+    pub max_retries: Option<u32>,
+}
+
+impl BackoffOptions {
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(10)
+    }
+
+    /// Delay before retry number `attempt` (starting at `1`).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let initial = self.initial_delay_ms.unwrap_or(250) as f64;
+        let max = self.max_delay_ms.unwrap_or(10_000) as f64;
+        let multiplier = self.multiplier.unwrap_or(2.0).max(1.0);
+        let delay = initial * multiplier.powi(attempt.saturating_sub(1) as i32);
+        Duration::from_millis(delay.min(max) as u64)
+    }
+}
+
+/// Which APIs get registered, e.g. to ship without `fs` access.
+///
+/// Entries are exact names (`webview.openDevtools`) or whole namespaces
+/// (`clipboard.*`). An API is registered if `allow` is empty or matches it and
+/// `deny` does not; left out APIs answer `404` like unknown ones.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiFilter {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl ApiFilter {
+    fn matches(patterns: &[String], name: &str) -> bool {
+        patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix(".*") {
+                Some(namespace) => name
+                    .strip_prefix(namespace)
+                    .is_some_and(|rest| rest.starts_with('.')),
+                None => pattern == name,
+            })
+    }
+
+    pub fn permits(&self, name: &str) -> bool {
+        (self.allow.is_empty() || Self::matches(&self.allow, name))
+            && !Self::matches(&self.deny, name)
+    }
+}
+
+/// Runtime configuration for the native side of a webframe.
+///
+/// Passed as the optional `runtime_cfg` JSON to `create_webframe`; every
+/// field falls back to a sensible default when omitted.
+#[allow(dead_code)]
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeOptions {
+    /// Milliseconds to wait for the event loop to answer a request.
+    pub request_timeout_ms: Option<u64>,
+    /// Permission bits of the Unix socket file (e.g. `384` for `0o600`).
+    pub socket_mode: Option<u32>,
+    /// Failed `system.auth` frames tolerated before the connection is closed.
+    pub max_auth_attempts: Option<u32>,
+    /// Client connections served at the same time; further ones are refused
+    /// with `503` (default `16`). The Python side holds a single connection,
+    /// however many calls it has in flight.
+    pub max_connections: Option<usize>,
+    /// Largest request frame accepted, in bytes (default 16 MiB). A client
+    /// announcing a larger one is answered with `413` and disconnected
+    /// before anything is allocated for it.
+    pub max_request_bytes: Option<usize>,
+    /// Encoding of request/response frames after the handshake (default JSON).
+    pub codec: Option<Codec>,
+    /// Directories the `fs` API may touch; the API is disabled when empty.
+    pub fs_roots: Option<Vec<std::path::PathBuf>>,
+    /// Backoff for rebuilding the connection listener after it failed.
+    pub reconnect: Option<BackoffOptions>,
+    /// Scheduler of the async runtime (default `multiThread`).
+    pub runtime_flavor: Option<RuntimeFlavor>,
+    /// Worker threads of the `multiThread` runtime (default `2`).
+    pub worker_threads: Option<usize>,
+    /// Shut down gracefully on SIGINT/SIGTERM (Ctrl-C on Windows); off by
+    /// default so Python keeps ownership of signal handling.
+    pub handle_signals: Option<bool>,
+    /// When set, closing the window is first announced as `closeRequested`
+    /// and held for up to this many milliseconds for `window.resolveClose`;
+    /// without an answer the window closes anyway.
+    pub close_confirm_timeout_ms: Option<u64>,
+    /// Forward `moved` and `resized` at most once per this many milliseconds
+    /// during a drag (default `100`), followed by `moveEnd`/`resizeEnd` once
+    /// the window stood still for as long. `windowStateChanged` waits as long
+    /// for the state to settle.
+    pub window_event_interval_ms: Option<u64>,
+    /// Forward key presses the window receives as `keyDown`/`keyUp` and
+    /// modifier changes as `modifiersChanged` (default `false`). Keys typed
+    /// while the webview has focus go to the page and are not forwarded.
+    pub forward_keys: Option<bool>,
+    /// APIs to register; all of them when omitted.
+    #[serde(default)]
+    pub apis: ApiFilter,
+    /// Reject window options with keys no option reads, e.g. the typo
+    /// `decoration`, instead of only logging them (default `false`).
+    pub strict_options: Option<bool>,
+    /// Whether devtools may be enabled at all (default `true`); `false`
+    /// overrides `WebViewOptions.devtools`, e.g. for production builds.
+    pub devtools: Option<bool>,
+    /// Minimum level of native log output: `error`, `warn` (default),
+    /// `info`, `debug`, `trace` or `off`.
+    pub log_level: Option<String>,
+    /// Format of the native log output (default `text`).
+    pub log_format: Option<LogFormat>,
+    /// Shared secret every connection must present first.
+    ///
+    /// Handed to `create_webframe` separately and never read from JSON.
+    #[serde(skip)]
+    pub auth_token: Option<String>,
+    /// Namespaces of the registered APIs, reported by `system.handshake`.
+    ///
+    /// Filled in by the WebFrame once its APIs are registered and never read
+    /// from JSON.
+    #[serde(skip)]
+    pub api_namespaces: Vec<String>,
+}
+
+impl RuntimeOptions {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.request_timeout_ms
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT_MS),
+        )
+    }
+
+    pub fn socket_mode(&self) -> u32 {
+        self.socket_mode.unwrap_or(DEFAULT_SOCKET_MODE)
+    }
+
+    pub fn max_auth_attempts(&self) -> u32 {
+        self.max_auth_attempts
+            .unwrap_or(DEFAULT_MAX_AUTH_ATTEMPTS)
+            .max(1)
+    }
+
+    pub fn max_request_bytes(&self) -> usize {
+        self.max_request_bytes.unwrap_or(DEFAULT_MAX_REQUEST_BYTES)
+    }
+
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+            .unwrap_or(DEFAULT_MAX_CONNECTIONS)
+            .max(1)
+    }
+
+    pub fn codec(&self) -> Codec {
+        self.codec.unwrap_or_default()
+    }
+
+    pub fn fs_roots(&self) -> &[std::path::PathBuf] {
+        self.fs_roots.as_deref().unwrap_or_default()
+    }
+
+    pub fn runtime_flavor(&self) -> RuntimeFlavor {
+        self.runtime_flavor.unwrap_or_default()
+    }
+
+    pub fn worker_threads(&self) -> usize {
+        self.worker_threads.unwrap_or(DEFAULT_WORKER_THREADS).max(1)
+    }
+
+    pub fn handle_signals(&self) -> bool {
+        self.handle_signals.unwrap_or(false)
+    }
+
+    pub fn close_confirm_timeout(&self) -> Option<Duration> {
+        self.close_confirm_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn window_event_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.window_event_interval_ms
+                .unwrap_or(DEFAULT_WINDOW_EVENT_INTERVAL_MS),
+        )
+    }
+
+    pub fn forward_keys(&self) -> bool {
+        self.forward_keys.unwrap_or(false)
+    }
+
+    pub fn devtools(&self) -> bool {
+        self.devtools.unwrap_or(true)
+    }
+
+    pub fn log_level(&self) -> &str {
+        self.log_level.as_deref().unwrap_or("warn")
+    }
+
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format.unwrap_or_default()
+    }
+
+    pub fn reconnect(&self) -> BackoffOptions {
+        self.reconnect.clone().unwrap_or_default()
+    }
+}
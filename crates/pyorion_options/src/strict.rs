@@ -0,0 +1,119 @@
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde_json::Value;
+
+use crate::window::{WebViewOptions, WindowOptions};
+
+/// Deserializer that only records the field names a struct asks for.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// Names of the JSON keys `T` reads, after renaming.
+fn field_names<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+fn collect(value: &Value, fields: &[&str], prefix: &str, unknown: &mut Vec<String>) {
+    if let Value::Object(map) = value {
+        unknown.extend(
+            map.keys()
+                .filter(|key| !fields.contains(&key.as_str()))
+                .map(|key| format!("{}{}", prefix, key)),
+        );
+    }
+}
+
+/// Keys of window options JSON that no field reads, such as the typo
+/// `decoration`; nested webview keys are reported as `webview.<key>`.
+pub fn unknown_window_fields(json: &Value) -> Vec<String> {
+    let mut unknown = Vec::new();
+    collect(json, field_names::<WindowOptions>(), "", &mut unknown);
+    if let Some(webview) = json.get("webview") {
+        collect(
+            webview,
+            field_names::<WebViewOptions>(),
+            "webview.",
+            &mut unknown,
+        );
+    }
+    unknown
+}
+
+/// Applies `strictOptions` to the unknown keys of window options JSON:
+/// strict mode rejects them, otherwise they are returned to be warned about.
+pub fn check_window_fields(json: &Value, strict: bool) -> anyhow::Result<Vec<String>> {
+    let unknown = unknown_window_fields(json);
+    if strict && !unknown.is_empty() {
+        anyhow::bail!("Unknown window options: {}", unknown.join(", "));
+    }
+    Ok(unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn typos() -> Value {
+        json!({
+            "title": "demo",
+            "decoration": false,
+            "webview": { "url": "https://example.com", "devtool": true },
+        })
+    }
+
+    #[test]
+    fn typos_are_reported_with_their_path() {
+        assert_eq!(
+            unknown_window_fields(&typos()),
+            vec!["decoration", "webview.devtool"]
+        );
+        assert!(unknown_window_fields(&json!({ "decorations": false })).is_empty());
+    }
+
+    #[test]
+    fn lenient_mode_returns_typos_to_warn_about() {
+        assert_eq!(
+            check_window_fields(&typos(), false).unwrap(),
+            vec!["decoration", "webview.devtool"]
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_typos() {
+        let err = check_window_fields(&typos(), true).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown window options: decoration, webview.devtool"
+        );
+        assert!(
+            check_window_fields(&json!({ "title": "demo" }), true)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -3,8 +3,8 @@ use base64::engine::general_purpose::STANDARD;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use tao::dpi::{
-    LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position as DpiPosition,
-    Size as DpiSize,
+    LogicalPosition, LogicalSize, LogicalUnit, PhysicalPosition, PhysicalSize, PhysicalUnit,
+    PixelUnit, Position as DpiPosition, Size as DpiSize,
 };
 use tao::window::{
     CursorIcon as TaoCursorIcon, Icon as TaoWindowIcon, ProgressBarState as TaoProgressBarState,
@@ -13,7 +13,7 @@ use tao::window::{
 };
 use wry::Rect;
 
-#[derive(Deserialize, Clone, Debug, Serialize)]
+#[derive(Deserialize, Clone, Debug, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum UnitType {
     Logical,
@@ -61,6 +61,22 @@ impl From<Position> for PhysicalPosition<i32> {
     }
 }
 
+impl Size {
+    /// Width and height as the units of [`TaoWindowSizeConstraints`].
+    pub fn pixel_units(&self) -> (PixelUnit, PixelUnit) {
+        match DpiSize::from(self.clone()) {
+            DpiSize::Logical(size) => (
+                PixelUnit::Logical(LogicalUnit::new(size.width)),
+                PixelUnit::Logical(LogicalUnit::new(size.height)),
+            ),
+            DpiSize::Physical(size) => (
+                PixelUnit::Physical(PhysicalUnit::new(size.width as i32)),
+                PixelUnit::Physical(PhysicalUnit::new(size.height as i32)),
+            ),
+        }
+    }
+}
+
 impl From<Size> for LogicalSize<f64> {
     fn from(value: Size) -> Self {
         LogicalSize {
@@ -103,6 +119,21 @@ impl From<Size> for DpiSize {
     }
 }
 
+impl WebViewBounds {
+    /// Rejects negative, zero-sized or incomplete bounds.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (axis, value) in [("width", self.size.width), ("height", self.size.height)] {
+            match value {
+                None => anyhow::bail!("Bounds {} is required", axis),
+                Some(v) if v < 0 => anyhow::bail!("Bounds {} must not be negative ({})", axis, v),
+                Some(0) => anyhow::bail!("Bounds {} must be larger than zero", axis),
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
 impl From<WebViewBounds> for Rect {
     fn from(value: WebViewBounds) -> Self {
         Rect {
@@ -250,6 +281,15 @@ impl From<Theme> for TaoTheme {
     }
 }
 
+impl From<TaoTheme> for Theme {
+    fn from(theme: TaoTheme) -> Self {
+        match theme {
+            TaoTheme::Dark => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum UserAttentionType {
@@ -288,12 +328,22 @@ pub struct Icon {
 }
 
 impl Icon {
-    #[allow(dead_code)]
+    /// Loads the image at `path` (PNG, ICO, JPEG or anything else the
+    /// `image` crate detects) as a window icon.
+    ///
+    /// Errors name the icon file, so they read well as the message of the
+    /// exception `create_webframe` raises.
     pub fn to_icon(&self) -> anyhow::Result<TaoWindowIcon> {
-        let bytes = std::fs::read(&self.path)?;
-        let image = image::load_from_memory(&bytes)?.to_rgba8();
+        use anyhow::Context;
+
+        let bytes = std::fs::read(&self.path)
+            .with_context(|| format!("cannot read icon file {}", self.path))?;
+        let image = image::load_from_memory(&bytes)
+            .with_context(|| format!("cannot decode icon file {}", self.path))?
+            .to_rgba8();
         let (width, height) = image.dimensions();
-        Ok(TaoWindowIcon::from_rgba(image.into_raw(), width, height)?)
+        TaoWindowIcon::from_rgba(image.into_raw(), width, height)
+            .with_context(|| format!("invalid icon {}", self.path))
     }
 }
 
@@ -306,6 +356,15 @@ pub struct Dimensions {
     pub height: u32,
 }
 
+impl From<PhysicalSize<u32>> for Dimensions {
+    fn from(size: PhysicalSize<u32>) -> Self {
+        Dimensions {
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
 /// Serde-kompatibles Constraints-Objekt
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -317,6 +376,33 @@ pub struct WindowSizeConstraints {
     pub unit: UnitType,
 }
 
+impl WindowSizeConstraints {
+    /// Checks that no bound is negative and that min ≤ max on each axis.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let axes = [
+            ("width", self.min_width, self.max_width),
+            ("height", self.min_height, self.max_height),
+        ];
+        for (axis, min, max) in axes {
+            if min.is_some_and(|v| v < 0.0) || max.is_some_and(|v| v < 0.0) {
+                anyhow::bail!("Size constraints for {} must not be negative", axis);
+            }
+            if let (Some(min), Some(max)) = (min, max)
+                && min > max
+            {
+                anyhow::bail!(
+                    "Minimum {} ({}) is larger than maximum {} ({})",
+                    axis,
+                    min,
+                    axis,
+                    max
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
 impl From<WindowSizeConstraints> for TaoWindowSizeConstraints {
     fn from(c: WindowSizeConstraints) -> Self {
         let (min_width, min_height, max_width, max_height) = match c.unit {
@@ -374,6 +460,15 @@ pub struct MonitorVideoMode {
     pub refresh_rate: u16,
 }
 
+/// Wished-for properties of a video mode; omitted fields match anything.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoModeQuery {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub refresh_rate: Option<u16>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Monitor {
@@ -389,6 +484,31 @@ pub struct Monitor {
     pub video_modes: Vec<MonitorVideoMode>,
 }
 
+/// Usable rectangle of a monitor, excluding taskbars, docks and panels.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkArea {
+    /// The top-left corner, in physical pixels.
+    pub position: MonitorPosition,
+    /// The size, in physical pixels.
+    pub size: Dimensions,
+    /// Whether the platform did not report a work area and the full monitor
+    /// bounds were returned instead.
+    pub fallback: bool,
+}
+
+/// A point relative to the top-left corner of a monitor.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalPosition {
+    /// The name of the monitor.
+    pub monitor: Option<String>,
+    /// The x offset from the monitor's left edge, in physical pixels.
+    pub x: i32,
+    /// The y offset from the monitor's top edge, in physical pixels.
+    pub y: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MonitorPosition {
@@ -398,6 +518,15 @@ pub struct MonitorPosition {
     pub y: i32,
 }
 
+impl From<PhysicalPosition<i32>> for MonitorPosition {
+    fn from(position: PhysicalPosition<i32>) -> Self {
+        MonitorPosition {
+            x: position.x,
+            y: position.y,
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
@@ -425,20 +554,350 @@ pub struct WindowOptions {
     pub visible: Option<bool>,
     pub visible_on_all_workspaces: Option<bool>,
     pub window_icon: Option<Icon>,
+    /// macOS: Makes the title bar transparent.
+    pub titlebar_transparent: Option<bool>,
+    /// macOS: Extends the content view under the title bar.
+    pub full_size_content_view: Option<bool>,
+    /// macOS: Hides the window title.
+    pub title_hidden: Option<bool>,
+    /// macOS: Moves the traffic-light buttons, e.g. to fit a custom title bar.
+    pub traffic_light_inset: Option<Position>,
+    /// Windows / Linux: Keeps the window off the taskbar, e.g. for tool windows.
+    pub skip_taskbar: Option<bool>,
+    /// Windows: HWND of a window that owns this one; an owned window stays
+    /// on top of its owner and is hidden with it.
+    pub owner_window: Option<isize>,
+    /// Windows: HWND of a window this one is embedded into as a child.
+    pub parent_window: Option<isize>,
+    /// Windows: Icon shown on the taskbar instead of `windowIcon`.
+    pub taskbar_icon: Option<Icon>,
+    /// Linux: GTK application id (e.g. `org.example.App`), used by GNOME to
+    /// group taskbar entries and attribute notifications.
+    pub app_id: Option<String>,
+    /// Linux: Double-buffered GTK rendering (default `true`).
+    pub double_buffered: Option<bool>,
+    /// Linux: Let GTK paint the transparent background (default `true`);
+    /// turn off to draw it yourself.
+    pub transparent_draw: Option<bool>,
+    /// Linux: Work around transparent WebKitGTK windows rendering black by
+    /// disabling the DMA-BUF renderer. Costs some GPU acceleration.
+    pub webkit_transparency_workaround: Option<bool>,
+    /// Hand keyboard focus to the webview whenever the window is shown again,
+    /// so typing reaches the page instead of the native frame.
+    pub focus_webview_on_show: Option<bool>,
     pub webview: WebViewOptions,
 }
+/// Startup state of a window after conflicting flags were reconciled.
+///
+/// Precedence, independent of platform timing:
+/// - `innerSize` is the restored size; `maximized` and `fullscreen` win at
+///   startup and the window returns to `innerSize` when leaving them.
+/// - `fullscreen` wins over `maximized`.
+/// - A hidden or non-focusable window does not take focus; show it with
+///   `window.setVisible(true, focus=True)` instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitialState {
+    pub visible: bool,
+    pub focused: bool,
+    pub maximized: bool,
+    pub fullscreen: bool,
+    /// One note per flag that was overridden, for logging.
+    pub overrides: Vec<&'static str>,
+}
+
+impl WindowOptions {
+    /// Whether showing the window again hands keyboard focus to the webview.
+    pub fn focus_webview_on_show(&self) -> bool {
+        self.focus_webview_on_show.unwrap_or(false)
+    }
+
+    /// Resolves the startup flags by the precedence of [`InitialState`].
+    pub fn initial_state(&self) -> InitialState {
+        let mut overrides = Vec::new();
+        let visible = self.visible.unwrap_or(true);
+        let fullscreen = self.fullscreen.unwrap_or(false);
+        let mut maximized = self.maximized.unwrap_or(false);
+        let mut focused = self.focused.unwrap_or(true);
+
+        if fullscreen && maximized {
+            maximized = false;
+            overrides.push("maximized is ignored because fullscreen wins");
+        }
+        if focused && !visible {
+            focused = false;
+            if self.focused == Some(true) {
+                overrides.push("focused is ignored because the window starts hidden");
+            }
+        }
+        if focused && self.focusable == Some(false) {
+            focused = false;
+            if self.focused == Some(true) {
+                overrides.push("focused is ignored because the window is not focusable");
+            }
+        }
+
+        InitialState {
+            visible,
+            focused,
+            maximized,
+            fullscreen,
+            overrides,
+        }
+    }
+
+    /// Checks the options for contradictions `serde` cannot catch.
+    ///
+    /// Collects every problem instead of stopping at the first one, so the
+    /// returned error lists all of them at once.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut problems = Vec::new();
+
+        let sizes = [
+            ("innerSize", &self.inner_size),
+            ("minInnerSize", &self.min_inner_size),
+            ("maxInnerSize", &self.max_inner_size),
+            (
+                "webview.bounds.size",
+                &self.webview.bounds.as_ref().map(|b| b.size.clone()),
+            ),
+        ];
+        for (name, size) in sizes {
+            let Some(size) = size else { continue };
+            for (axis, value) in [("width", size.width), ("height", size.height)] {
+                if let Some(value) = value
+                    && value < 0
+                {
+                    problems.push(format!(
+                        "{}.{} must not be negative ({})",
+                        name, axis, value
+                    ));
+                }
+            }
+        }
+
+        // Bounds are only comparable when both sides use the same unit
+        let pairs = [
+            (
+                "minInnerSize",
+                &self.min_inner_size,
+                "maxInnerSize",
+                &self.max_inner_size,
+            ),
+            (
+                "minInnerSize",
+                &self.min_inner_size,
+                "innerSize",
+                &self.inner_size,
+            ),
+            (
+                "innerSize",
+                &self.inner_size,
+                "maxInnerSize",
+                &self.max_inner_size,
+            ),
+        ];
+        for (small_name, small, large_name, large) in pairs {
+            let (Some(small), Some(large)) = (small, large) else {
+                continue;
+            };
+            if small.unit != large.unit {
+                continue;
+            }
+            let axes = [
+                ("width", small.width, large.width),
+                ("height", small.height, large.height),
+            ];
+            for (axis, lo, hi) in axes {
+                if let (Some(lo), Some(hi)) = (lo, hi)
+                    && lo > hi
+                {
+                    problems.push(format!(
+                        "{}.{} ({}) is larger than {}.{} ({})",
+                        small_name, axis, lo, large_name, axis, hi
+                    ));
+                }
+            }
+        }
+
+        for (name, icon) in [
+            ("windowIcon", &self.window_icon),
+            ("taskbarIcon", &self.taskbar_icon),
+        ] {
+            if let Some(icon) = icon
+                && !std::path::Path::new(&icon.path).is_file()
+            {
+                problems.push(format!("{}.path does not exist: {}", name, icon.path));
+            }
+        }
+
+        for (name, handle) in [
+            ("ownerWindow", self.owner_window),
+            ("parentWindow", self.parent_window),
+        ] {
+            if handle == Some(0) {
+                problems.push(format!("{} must be a nonzero window handle", name));
+            }
+        }
+        if self.owner_window.is_some() && self.parent_window.is_some() {
+            problems.push("ownerWindow and parentWindow cannot both be set".to_string());
+        }
+
+        match (&self.webview.url, &self.webview.html) {
+            (Some(_), Some(_)) => {
+                problems.push("webview.url and webview.html cannot both be set".to_string())
+            }
+            (Some(url), None) => match url::Url::parse(url) {
+                Ok(parsed) if ["http", "https", "app"].contains(&parsed.scheme()) => {}
+                Ok(parsed) => problems.push(format!(
+                    "webview.url must be an http, https or app URL, not {}:",
+                    parsed.scheme()
+                )),
+                Err(e) => problems.push(format!("webview.url is not a valid URL: {}", e)),
+            },
+            (None, Some(html)) if html.trim().is_empty() => {
+                problems.push("webview.html must not be empty".to_string())
+            }
+            _ => {}
+        }
+
+        for (i, arg) in self.webview.webview2_args.iter().flatten().enumerate() {
+            if arg.trim().is_empty() {
+                problems.push(format!("webview.webview2Args[{}] must not be empty", i));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!("Invalid window options:\n  - {}", problems.join("\n  - "))
+        }
+    }
+}
+
+/// Where the `app://` protocol reads the bundled frontend from.
+///
+/// Exactly one of `dir` and `bundle` has to be set.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetsOptions {
+    /// Directory served as the root of `app://localhost/`.
+    pub dir: Option<String>,
+    /// ZIP archive loaded into memory once and served instead of a directory.
+    pub bundle: Option<String>,
+    /// Page served for `/` and directories (default `index.html`).
+    pub index: Option<String>,
+}
+
+/// Protocol spoken with a [`ProxyConfig`] server.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyScheme {
+    /// HTTP `CONNECT`.
+    Http,
+    /// SOCKSv5.
+    Socks5,
+}
+
+/// Proxy the webview routes its traffic through.
+///
+/// Written as a URL, `http://host:port` or `socks5://host:port`. Credentials
+/// (`user:pass@host`) are parsed but rejected when the webview is built,
+/// since no backend can pass them on.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct ProxyConfig {
+    pub scheme: ProxyScheme,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl std::str::FromStr for ProxyConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url =
+            url::Url::parse(s).map_err(|e| anyhow::anyhow!("Invalid proxy {:?}: {}", s, e))?;
+        let scheme = match url.scheme() {
+            "http" => ProxyScheme::Http,
+            "socks5" => ProxyScheme::Socks5,
+            other => anyhow::bail!(
+                "Invalid proxy {:?}: unsupported scheme {:?}, expected http or socks5",
+                s,
+                other
+            ),
+        };
+        let host = url
+            .host_str()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Invalid proxy {:?}: missing host", s))?;
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| anyhow::anyhow!("Invalid proxy {:?}: missing port", s))?;
+        Ok(Self {
+            scheme,
+            host: host.to_string(),
+            port,
+            username: Some(url.username())
+                .filter(|user| !user.is_empty())
+                .map(str::to_string),
+            password: url.password().map(str::to_string),
+        })
+    }
+}
+
+impl TryFrom<String> for ProxyConfig {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl ProxyConfig {
+    /// Converts into the webview's proxy settings.
+    pub fn to_wry(&self) -> anyhow::Result<wry::ProxyConfig> {
+        if self.username.is_some() || self.password.is_some() {
+            anyhow::bail!("Proxy authentication is not supported by the webview");
+        }
+        let endpoint = wry::ProxyEndpoint {
+            host: self.host.clone(),
+            port: self.port.to_string(),
+        };
+        Ok(match self.scheme {
+            ProxyScheme::Http => wry::ProxyConfig::Http(endpoint),
+            ProxyScheme::Socks5 => wry::ProxyConfig::Socks5(endpoint),
+        })
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct WebViewOptions {
     pub label: Option<String>,
     pub render_protocol: Option<String>,
+    pub assets: Option<AssetsOptions>,
+    /// Page loaded first: an `http(s)://` URL or an `app://` asset URL.
+    /// Takes precedence over the page `renderProtocol` or `assets` imply;
+    /// cannot be combined with `html`.
+    pub url: Option<String>,
+    /// Inline HTML loaded first instead of a URL; its origin is `null`.
+    pub html: Option<String>,
     pub transparent: Option<bool>,
     pub visible: Option<bool>,
     pub devtools: Option<bool>,
     pub incognito: Option<bool>,
     pub user_agent: Option<String>,
     pub initialization_script: Option<String>,
+    /// Inject the `window.pyorion.invoke` helper next to the socket script
+    /// (default `false`); leave it off to ship a customized copy through
+    /// `initialization_scripts` instead.
+    pub invoke_helper: Option<bool>,
+    /// Further scripts run on every page load, in order, after
+    /// `initialization_script`.
+    pub initialization_scripts: Option<Vec<String>>,
     pub accept_first_mouse: Option<bool>,
     pub autoplay: Option<bool>,
     pub focused: Option<bool>,
@@ -447,10 +906,37 @@ pub struct WebViewOptions {
     pub background_color: Option<(u8, u8, u8, u8)>,
     pub bounds: Option<WebViewBounds>, // x, y, w, h
     pub headers: Option<std::collections::HashMap<String, String>>,
-    pub proxy_config: Option<String>,
+    /// Extra headers added to every `app://` response (e.g. CORS, COOP/COEP).
+    pub asset_headers: Option<std::collections::HashMap<String, String>>,
+    pub proxy_config: Option<ProxyConfig>,
+    /// URL prefixes the webview may navigate to besides its own pages;
+    /// anything else is blocked and reported as `navigationBlocked`.
+    pub navigation_allowlist: Option<Vec<String>>,
+    /// When set, a navigation the allowlist rejects is cancelled and
+    /// announced as `navigationRequested` instead of blocked; it is loaded
+    /// again if `webview.resolveNavigation` allows it within this many
+    /// milliseconds, or if no answer comes in time.
+    pub navigation_confirm_timeout_ms: Option<u64>,
+    /// Where cookies, localStorage and IndexedDB persist; defaults to
+    /// `<local data dir>/pyorion/<app>/<label>`, with `<app>` taken from
+    /// `appId`, else the window title, else the executable name.
+    pub data_directory: Option<String>,
+    /// Emit `fileDrop` events for files dragged onto the webview (default `true`).
+    pub file_drop: Option<bool>,
+    /// Let elements marked with `data-pyorion-drag-region` or the CSS
+    /// `app-region: drag` move the window, e.g. for custom title bars.
+    pub enable_drag_regions: Option<bool>,
     pub zoom_hotkeys: Option<bool>,
     pub background_throttling: Option<bool>,
     pub back_forward_navigation_gestures: Option<bool>,
+    /// Windows only: extra WebView2 browser arguments, e.g.
+    /// `--disable-features=...`. They replace the arguments wry passes by
+    /// default, and flags WebView2 does not accept can keep it from starting.
+    pub webview2_args: Option<Vec<String>>,
+    /// Windows only: WebView2 user data folder; takes precedence over
+    /// `data_directory`. Webviews with different `webview2_args` need
+    /// different folders.
+    pub webview2_data_dir: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -568,3 +1054,185 @@ pub struct WindowEffectsConfig {
     /// on Windows 10 v1903+. Doesn't have any effect on Windows 7 or Windows 11.
     pub color: Option<Color>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(width: i32, height: i32) -> Option<Size> {
+        Some(Size {
+            width: Some(width),
+            height: Some(height),
+            unit: UnitType::Logical,
+        })
+    }
+
+    fn problems(options: &WindowOptions) -> String {
+        options.validate().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn min_larger_than_max_is_rejected() {
+        let options = WindowOptions {
+            min_inner_size: size(800, 600),
+            max_inner_size: size(400, 600),
+            ..Default::default()
+        };
+        assert!(
+            problems(&options)
+                .contains("minInnerSize.width (800) is larger than maxInnerSize.width (400)")
+        );
+    }
+
+    #[test]
+    fn negative_sizes_are_rejected() {
+        let options = WindowOptions {
+            inner_size: size(-1, 600),
+            max_inner_size: size(800, -2),
+            ..Default::default()
+        };
+        let problems = problems(&options);
+        assert!(problems.contains("innerSize.width must not be negative (-1)"));
+        assert!(problems.contains("maxInnerSize.height must not be negative (-2)"));
+    }
+
+    #[test]
+    fn missing_icon_file_is_rejected() {
+        let options = WindowOptions {
+            window_icon: Some(Icon {
+                path: "does/not/exist.png".to_string(),
+            }),
+            ..Default::default()
+        };
+        assert!(problems(&options).contains("windowIcon.path does not exist: does/not/exist.png"));
+    }
+
+    #[test]
+    fn consistent_options_pass() {
+        let options = WindowOptions {
+            min_inner_size: size(400, 300),
+            inner_size: size(800, 600),
+            max_inner_size: size(1200, 900),
+            ..Default::default()
+        };
+        options.validate().unwrap();
+    }
+
+    #[test]
+    fn maximized_keeps_inner_size_for_restoring() {
+        let options = WindowOptions {
+            maximized: Some(true),
+            inner_size: size(800, 600),
+            ..Default::default()
+        };
+        let state = options.initial_state();
+        assert!(state.maximized);
+        assert!(!state.fullscreen);
+        assert!(state.overrides.is_empty());
+        options.validate().unwrap();
+    }
+
+    #[test]
+    fn fullscreen_wins_over_maximized() {
+        let options = WindowOptions {
+            maximized: Some(true),
+            fullscreen: Some(true),
+            ..Default::default()
+        };
+        let state = options.initial_state();
+        assert!(state.fullscreen);
+        assert!(!state.maximized);
+        assert_eq!(
+            state.overrides,
+            vec!["maximized is ignored because fullscreen wins"]
+        );
+    }
+
+    #[test]
+    fn hidden_window_does_not_take_focus() {
+        let options = WindowOptions {
+            visible: Some(false),
+            focused: Some(true),
+            ..Default::default()
+        };
+        let state = options.initial_state();
+        assert!(!state.visible);
+        assert!(!state.focused);
+        assert_eq!(
+            state.overrides,
+            vec!["focused is ignored because the window starts hidden"]
+        );
+
+        // Only an explicit request for focus is worth a note
+        let options = WindowOptions {
+            visible: Some(false),
+            ..Default::default()
+        };
+        assert!(options.initial_state().overrides.is_empty());
+    }
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    #[test]
+    fn icon_loads_from_png() {
+        let icon = Icon {
+            path: fixture("icon.png"),
+        };
+        icon.to_icon().unwrap();
+    }
+
+    #[test]
+    fn missing_icon_file_names_the_path() {
+        let icon = Icon {
+            path: fixture("missing.png"),
+        };
+        let err = icon.to_icon().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("cannot read icon file {}", fixture("missing.png"))
+        );
+    }
+
+    #[test]
+    fn undecodable_icon_file_names_the_path() {
+        let icon = Icon {
+            path: format!("{}/Cargo.toml", env!("CARGO_MANIFEST_DIR")),
+        };
+        let err = icon.to_icon().unwrap_err();
+        assert!(err.to_string().starts_with("cannot decode icon file"));
+    }
+
+    #[test]
+    fn focus_webview_on_show_is_off_by_default() {
+        assert!(!WindowOptions::default().focus_webview_on_show());
+        let options = WindowOptions {
+            focus_webview_on_show: Some(true),
+            ..Default::default()
+        };
+        assert!(options.focus_webview_on_show());
+    }
+
+    #[test]
+    fn logical_size_converts_to_a_logical_dpi_size() {
+        match DpiSize::from(size(800, 600).unwrap()) {
+            DpiSize::Logical(size) => assert_eq!((size.width, size.height), (800.0, 600.0)),
+            other => panic!("expected a logical size, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn physical_size_converts_to_a_physical_dpi_size() {
+        let size = Size {
+            width: Some(1600),
+            height: Some(-5),
+            unit: UnitType::Physical,
+        };
+        match DpiSize::from(size) {
+            // Negative sides clamp to zero instead of wrapping around
+            DpiSize::Physical(size) => assert_eq!((size.width, size.height), (1600, 0)),
+            other => panic!("expected a physical size, got {:?}", other),
+        }
+    }
+}
@@ -9,6 +9,7 @@ Exported symbols
 
 * :func:`command` — Decorator to register Python functions as callable
   commands from the frontend.
+* :func:`listen` — Decorator to register listeners for native events.
 * :class:`PyOrion` — Entry point to start the runtime environment.
 * :class:`WebView` — Abstraction for managing embedded browser views.
 * :class:`Window` — Abstraction for creating and managing application windows.
@@ -16,7 +17,7 @@ Exported symbols
 """
 
 from . import api, setup
-from .pyinvoke import command
+from .pyinvoke import command, listen
 from .runtime import launch
 
 
@@ -24,6 +25,7 @@ __all__ = [
     "api",
     "command",
     "launch",
+    "listen",
     "setup",
     "types",
 ]
@@ -4,13 +4,20 @@ from typing import Any
 __version__: str
 """The package version as defined in `Cargo.toml`, modified to match python's versioning semantics."""
 
+INVOKE_SCRIPT: str
+"""Source of the injected ``window.pyorion.invoke`` helper, for customized copies."""
+
 def create_webframe(
     config: str,
     sock_cfg: str | None,
     uds_name: str,
     close_event: multiprocessing.Event,  # type: ignore
+    runtime_cfg: str | None = None,
+    auth_token: str | None = None,
 ) -> Any: ...
 async def send_event_over_platform(
     name: str,
     message: str,
+    token: str | None = None,
+    codec: str | None = None,
 ) -> Any: ...
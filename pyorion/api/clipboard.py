@@ -11,7 +11,7 @@ for both text and images via the Rust event loop.
 import base64
 
 from pyorion.runtime.runtime_handle import event_register
-from pyorion.setup.types import ClipboardImage
+from pyorion.setup.types import ClipboardImage, ClipboardSetImageResult
 
 
 class ClipboardAPI:
@@ -47,16 +47,22 @@ class ClipboardAPI:
         """
         return await event_register("clipboard.clear")
 
-    async def set_image(self, width: int, height: int, raw_bytes: bytes) -> str:
+    async def set_image(
+        self, width: int, height: int, raw_bytes: bytes
+    ) -> ClipboardSetImageResult:
         """Set image in the clipboard.
 
         :param width: The width of the image in pixels.
         :param height: The height of the image in pixels.
-        :param raw_bytes: The raw image data (e.g., RGB or RGBA).
-        :return: A confirmation message from the event system.
+        :param raw_bytes: The raw RGBA image data, ``width * height * 4`` bytes.
+        :return: Whether the image was copied and, if not, why.
         """
         b64 = base64.b64encode(raw_bytes).decode("ascii")
-        return await event_register("clipboard.set_image", args=[width, height, b64])
+        return await event_register(
+            "clipboard.set_image",
+            args=[width, height, b64],
+            result_type=ClipboardSetImageResult,
+        )
 
     async def get_image(self) -> ClipboardImage:
         """Retrieve image from the clipboard.
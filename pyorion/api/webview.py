@@ -4,6 +4,8 @@ Wrapper around the Rust event loop backend to manage developer tools
 and WebView state.
 """
 
+from typing import Any
+
 from pyorion.runtime.runtime_handle import event_register
 
 
@@ -36,3 +38,70 @@ class WebView:
         :rtype: bool
         """
         return await event_register("webview.closeDevtools", None, result_type=bool)
+
+    async def post_message(self, message: Any) -> None:
+        """Push a JSON value to the page without waiting for a request.
+
+        The page receives it through ``window.__PYORION_EVENT__(message)``,
+        which it has to define; messages sent before that are dropped.
+
+        :param message: Any JSON serializable value.
+        :type message: Any
+        """
+        await event_register("webview.postMessage", [message])
+
+    async def resolve_navigation(self, id: int, allow: bool) -> bool:
+        """Answer a ``navigationRequested`` event.
+
+        :param id: The ``id`` of the event.
+        :param allow: ``True`` loads the URL, ``False`` keeps it blocked.
+        :return: ``False`` if the navigation was no longer held, e.g. because
+            its timeout ran out and it was allowed.
+        :rtype: bool
+        """
+        return await event_register(
+            "webview.resolveNavigation", [id, allow], result_type=bool
+        )
+
+    async def get_scroll_position(self) -> dict[str, float]:
+        """Return the scroll offset of the page as ``{"x": ..., "y": ...}``.
+
+        :raises ApiError: ``503`` while the page is still loading.
+        """
+        return await event_register("webview.getScrollPosition", None, result_type=dict)
+
+    async def set_scroll_position(self, x: float, y: float) -> dict[str, float]:
+        """Scroll the page, e.g. to restore the offset after a reload.
+
+        :return: The offset the page ended up at, clamped to the document.
+        :raises ApiError: ``503`` while the page is still loading.
+        """
+        return await event_register(
+            "webview.setScrollPosition", [x, y], result_type=dict
+        )
+
+    async def focus(self) -> bool:
+        """Move keyboard focus into the web content.
+
+        Useful after a native dialog closed and left the focus on the window
+        frame.
+
+        :return: ``True`` once the webview was focused.
+        """
+        return await event_register("webview.focus", None, result_type=bool)
+
+    async def reload(self) -> str:
+        """Reload the current page.
+
+        :return: The URL that was reloaded.
+        """
+        return await event_register("webview.reload", None, result_type=str)
+
+    async def reload_ignoring_cache(self) -> str:
+        """Reload the current page, revalidating it with the server.
+
+        Subresources the page references may still come from the cache.
+
+        :return: The URL that was reloaded.
+        """
+        return await event_register("webview.reloadIgnoringCache", None, result_type=str)
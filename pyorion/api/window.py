@@ -84,6 +84,33 @@ class Window:
         """
         return await event_register("window.set_visible", visible, result_type=bool)
 
+    async def resolve_close(self: "Window", allow: bool) -> bool:
+        """Answer a ``closeRequested`` event.
+
+        :param bool allow: ``True`` closes the window, ``False`` keeps it open.
+        :return: ``False`` if no close was pending (e.g. it timed out).
+        :rtype: bool
+        """
+        return await event_register("window.resolveClose", allow, result_type=bool)
+
+    async def set_skip_taskbar(self: "Window", skip: bool) -> bool:
+        """Hide the window from the taskbar and alt-tab, or show it again.
+
+        :raises ApiError: ``501`` on macOS, which has no per-window taskbar.
+        """
+        return await event_register("window.setSkipTaskbar", skip, result_type=bool)
+
+    async def set_opacity(self: "Window", opacity: float) -> float:
+        """Fade the whole window, decorations included.
+
+        :param float opacity: ``0.0`` (invisible) to ``1.0`` (opaque); values
+            outside are clamped.
+        :return: The opacity applied.
+        :raises ApiError: ``501`` on mobile platforms. On Linux it only takes
+            effect under a compositing window manager.
+        """
+        return await event_register("window.setOpacity", opacity, result_type=float)
+
     async def set_title(self: "Window", title: str) -> bool:
         """Set the title of the window.
 
@@ -185,12 +212,16 @@ class Window:
         return await event_register("window.set_cursor_icon", cursor, result_type=bool)
 
     async def set_cursor_position(self: "Window", position: Position) -> bool:
-        """Set the cursor position in window coordinates.
+        """Move the cursor, relative to the window's client area.
 
         Wrapper for ``tao::window::Window::set_cursor_position``.
+
+        :raises ApiError: ``501`` on mobile platforms, or the platform error
+            when moving the cursor was refused. Wayland ignores the move
+            without an error.
         """
         return await event_register(
-            "window.set_cursor_position",
+            "window.setCursorPosition",
             position.model_dump(by_alias=True),
             result_type=bool,
         )
@@ -379,6 +410,17 @@ class Window:
         """
         return await event_register("window.inner_size", {}, result_type=Dimensions)
 
+    async def will_resize_to(self: "Window", size: Size) -> Dimensions:
+        """Return the inner size the window would get for ``size``.
+
+        Applies the current size constraints without resizing the window.
+        """
+        return await event_register(
+            "window.willResizeTo",
+            size.model_dump(by_alias=True),
+            result_type=Dimensions,
+        )
+
     async def outer_size(self: "Window") -> Dimensions:
         """Return outer size.
 
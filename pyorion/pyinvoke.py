@@ -20,6 +20,9 @@ from pydantic import ValidationError, create_model
 
 
 _event_callbacks: dict[str, list[Callable[..., None | Awaitable[None]]]] = {}
+_event_listeners: dict[
+    str, list[tuple[Callable[..., None | Awaitable[None]], str | None]]
+] = {}
 _dependency_cache: dict[type, Any] = {}
 
 
@@ -64,6 +67,47 @@ def command(func_or_event: str | Callable | None = None) -> Callable:
     return decorator
 
 
+def listen(event: str, label: str | None = None) -> Callable:
+    """Register a function as a listener for a native event.
+
+    Native events (e.g. ``themeChanged``) are emitted by the Rust backend
+    and forwarded by the frontend bootstrap::
+
+        @listen("themeChanged")
+        def on_theme(payload: str) -> None:
+            print(f"Theme is now {payload}")
+
+    Events carry the ``label`` of the webview they originate from; with
+    ``label`` set, the listener only receives events of that webview.
+
+    :param event: Name of the native event.
+    :type event: str
+    :param label: Only receive events of the webview with this label.
+    :type label: str | None
+    :return: Decorator registering the listener.
+    :rtype: Callable
+    """
+
+    def decorator(func: Callable):
+        _event_listeners.setdefault(event, []).append((func, label))
+        return func
+
+    return decorator
+
+
+async def dispatch_event(event: str, payload: Any, label: str | None = None) -> None:
+    """Call every listener registered for a native event with its payload.
+
+    Listeners bound to a label only see events of the webview with that label.
+    """
+    for func, only in _event_listeners.get(event, []):
+        if only is not None and only != label:
+            continue
+        result = func(payload)
+        if inspect.isawaitable(result):
+            await result
+
+
 def _resolve_final_type(tp: Any) -> Any:
     """Resolve the base type from complex type hints."""
     origin = get_origin(tp)
@@ -14,7 +14,7 @@ import websockets
 from pydantic import BaseModel
 from websockets import ServerConnection
 
-from pyorion.pyinvoke import _event_callbacks, make_callback
+from pyorion.pyinvoke import _event_callbacks, dispatch_event, make_callback
 from pyorion.runtime import core
 from pyorion.utils import make_json_safe
 
@@ -36,6 +36,19 @@ async def handle_frontend_connections(websocket: ServerConnection) -> None:
                     logging.warning("Ignoring non-dict payload: %s", payload)
                     continue
 
+                if payload.get("type") == "ping":
+                    # Heartbeat of the webview socket, answered only to the sender
+                    await websocket.send(
+                        json.dumps({"type": "pong", "ts": payload.get("ts")})
+                    )
+                    continue
+
+                if "event" in payload:
+                    await dispatch_event(
+                        payload["event"], payload.get("payload"), payload.get("label")
+                    )
+                    continue
+
                 if all(
                     k in payload for k in ("cmd", "result_id", "error_id", "payload")
                 ):
@@ -84,14 +97,39 @@ async def handle_frontend_connections(websocket: ServerConnection) -> None:
         core.connected_clients.discard(websocket)
 
 
-async def create_websocket_server(url: str) -> None:
-    """Create and run the frontend WebSocket server (with optional path)."""
-    parsed = urlparse(url)
-    host, port = parsed.hostname, parsed.port
-    expected_path = parsed.path if parsed.path and parsed.path != "/" else None
+async def create_websocket_server(
+    url: str, bound: asyncio.Future[int] | None = None
+) -> None:
+    """Create and run the frontend WebSocket server (with optional path).
 
-    if not host or not port:
-        raise ValueError(f"Invalid WebSocket URL: {url}")
+    Port ``0`` lets the OS pick a free port; the port actually bound is
+    reported through ``bound`` once the server is listening. A bare
+    ``host:port`` is taken as ``ws://host:port``; other schemes than ``ws``
+    and ``wss`` are rejected.
+    """
+    try:
+        if "://" not in url:
+            url = f"ws://{url}"
+        parsed = urlparse(url)
+        if parsed.scheme not in ("ws", "wss"):
+            raise ValueError(
+                f"Invalid WebSocket URL: {url} (use ws:// or wss://, "
+                f"not {parsed.scheme}://)"
+            )
+        try:
+            host, port = parsed.hostname, parsed.port
+        except ValueError:
+            raise ValueError(
+                f"Invalid WebSocket URL: {url} (IPv6 hosts must be bracketed, "
+                "e.g. ws://[::1]:8765)"
+            ) from None
+        if not host or port is None:
+            raise ValueError(f"Invalid WebSocket URL: {url}")
+    except Exception as exc:
+        if bound is not None:
+            bound.set_exception(exc)
+        raise
+    expected_path = parsed.path if parsed.path and parsed.path != "/" else None
 
     async def handler(websocket: ServerConnection):
         path_received = websocket.request.path
@@ -105,5 +143,13 @@ async def create_websocket_server(url: str) -> None:
 
         await handle_frontend_connections(websocket)
 
-    async with websockets.serve(handler, host, port):
+    try:
+        server = await websockets.serve(handler, host, port)
+    except Exception as exc:
+        if bound is not None:
+            bound.set_exception(exc)
+        raise
+    async with server:
+        if bound is not None:
+            bound.set_result(next(iter(server.sockets)).getsockname()[1])
         await asyncio.Future()
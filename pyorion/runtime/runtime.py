@@ -6,19 +6,24 @@ subprocesses, and WebSocket communication.
 """
 
 import asyncio
+import json
+import os
+import secrets
 from collections.abc import Coroutine
 from multiprocessing import get_context
 from multiprocessing.context import SpawnProcess
 from pathlib import Path
+from typing import Literal
+from urllib.parse import urlparse, urlunparse
 
 from pydantic import AnyHttpUrl
 
 from pyorion._pyorion import create_webframe
-from pyorion.setup.types import WebSocketConfig, WindowOptions
+from pyorion.setup.types import TlsConfig, WebSocketConfig, WindowOptions
 
 from . import core
 from .connections import create_websocket_server
-from .runtime_handle import eventloop_sender
+from .runtime_handle import RuntimeHandle, activate_runtime, eventloop_sender
 
 
 shutdown_event = None  # Global shutdown event shared across the runtime
@@ -94,6 +99,22 @@ async def run_native_runtime(
     protocols: list[str] | None = None,
     auto_reconnect: bool = True,
     reconnect_interval: int = 3000,
+    tls: TlsConfig | None = None,
+    socket_global_name: str | None = None,
+    codec: Literal["json", "msgpack"] = "json",
+    fs_roots: list[str | Path] | None = None,
+    log_level: Literal["off", "error", "warn", "info", "debug", "trace"] = "warn",
+    log_format: Literal["text", "json"] = "text",
+    close_confirm_timeout_ms: int | None = None,
+    devtools: bool = True,
+    max_request_bytes: int = 16 * 1024 * 1024,
+    strict_options: bool = False,
+    api_allow: list[str] | None = None,
+    api_deny: list[str] | None = None,
+    window_event_interval_ms: int = 100,
+    forward_keys: bool = False,
+    runtime_flavor: Literal["multiThread", "currentThread"] = "multiThread",
+    worker_threads: int = 2,
 ) -> None:
     """Start the native runtime environment.
 
@@ -118,22 +139,91 @@ async def run_native_runtime(
     :type auto_reconnect: bool, optional
     :param reconnect_interval: Interval in milliseconds before attempting reconnect.
     :type reconnect_interval: int, optional
+    :param tls: Optional TLS settings used for ``wss://`` connections.
+    :type tls: TlsConfig | None, optional
+    :param socket_global_name: ``window`` property holding the connection
+        object instead of ``PyOrionConnections``.
+    :type socket_global_name: str | None, optional
+    :param codec: Frame encoding between Python and the WebFrame process.
+    :type codec: Literal["json", "msgpack"], optional
+    :param fs_roots: Directories the ``fs`` API may access; disabled if omitted.
+    :type fs_roots: list[str | Path] | None, optional
+    :param log_level: Minimum level of the WebFrame log output on stderr.
+    :type log_level: Literal["off", "error", "warn", "info", "debug", "trace"], optional
+    :param log_format: ``json`` writes one JSON object per log line.
+    :type log_format: Literal["text", "json"], optional
+    :param close_confirm_timeout_ms: Hold window closes for up to this many
+        milliseconds so a ``closeRequested`` listener can veto them through
+        ``Window.resolve_close``; closes happen right away if omitted.
+    :type close_confirm_timeout_ms: int | None, optional
+    :param devtools: ``False`` forbids devtools regardless of the webview
+        options; the devtools APIs then fail with ``403``.
+    :type devtools: bool, optional
+    :param max_request_bytes: Largest request the WebFrame accepts; larger
+        ones fail with ``413`` and drop the connection.
+    :type max_request_bytes: int, optional
+    :param strict_options: Fail on window option keys the WebFrame does not
+        know instead of logging and ignoring them.
+    :type strict_options: bool, optional
+    :param api_allow: Only register these APIs, given as exact names
+        (``webview.openDevtools``) or namespaces (``clipboard.*``).
+    :type api_allow: list[str] | None, optional
+    :param api_deny: Never register these APIs; calls to them fail with
+        ``404`` as for unknown APIs.
+    :type api_deny: list[str] | None, optional
+    :param window_event_interval_ms: Forward ``moved`` and ``resized`` at most
+        once per this many milliseconds while the window is dragged, then
+        ``moveEnd`` or ``resizeEnd`` once it stood still for as long.
+        ``windowStateChanged`` waits as long for the state to settle.
+    :type window_event_interval_ms: int, optional
+    :param forward_keys: Emit ``keyDown``/``keyUp`` and ``modifiersChanged``
+        for keys the window receives. Keys typed while the page has focus are
+        handled by the page and not forwarded.
+    :type forward_keys: bool, optional
+    :param runtime_flavor: Scheduler serving the WebFrame connections.
+        ``multiThread`` serves clients in parallel on ``worker_threads``
+        threads; ``currentThread`` runs everything on one background thread,
+        saving threads at the cost of throughput under concurrent clients.
+    :type runtime_flavor: Literal["multiThread", "currentThread"], optional
+    :param worker_threads: Worker threads of the ``multiThread`` runtime.
+    :type worker_threads: int, optional
     :return: None
     :rtype: None
     """
     socket_cfg = None
 
+    # Fresh secret per launch: only this process and the webframe know it.
+    # The pipe name is unique per launch so that webframes of several
+    # processes, or several launches in a row, never bind over each other's
+    # socket. Activated first so every task started below talks to this
+    # webframe.
+    runtime = RuntimeHandle(
+        pipe_name=f"pyframe_pipe_{os.getpid()}_{secrets.token_hex(4)}",
+        auth_token=secrets.token_urlsafe(32),
+        codec=codec,
+    )
+    activate_runtime(runtime)
+
     loop = asyncio.get_running_loop()
     if internal_proto and websocket_url is not None:
+        bound: asyncio.Future[int] = loop.create_future()
+        launch_background_task(create_websocket_server(str(websocket_url), bound))
+        port = await bound
+        if websocket_url.port == 0:
+            # The OS picked the port: hand the real one to the webview
+            parsed = urlparse(str(websocket_url))
+            netloc = f"{parsed.netloc.rsplit(':', 1)[0]}:{port}"
+            websocket_url = AnyHttpUrl(urlunparse(parsed._replace(netloc=netloc)))
         socket_cfg = WebSocketConfig(
             url=websocket_url,
             protocols=protocols,
             auto_reconnect=auto_reconnect,
             reconnect_interval=reconnect_interval,
+            tls=tls,
+            global_name=socket_global_name,
         )
-        launch_background_task(create_websocket_server(str(websocket_url)))
 
-    launch_background_task(eventloop_sender())
+    launch_background_task(eventloop_sender(runtime))
     socket_cfg_json = (
         socket_cfg.model_dump_json(by_alias=True) if socket_cfg is not None else None
     )
@@ -146,7 +236,27 @@ async def run_native_runtime(
         shutdown_event = close_event
         proc = ctx.Process(
             target=create_webframe,
-            args=(config, socket_cfg_json, "pyframe_pipe", shutdown_event),
+            args=(config, socket_cfg_json, runtime.pipe_name, shutdown_event),
+            kwargs={
+                "runtime_cfg": json.dumps(
+                    {
+                        "codec": codec,
+                        "fsRoots": [str(root) for root in fs_roots or []],
+                        "logLevel": log_level,
+                        "logFormat": log_format,
+                        "closeConfirmTimeoutMs": close_confirm_timeout_ms,
+                        "devtools": devtools,
+                        "strictOptions": strict_options,
+                        "maxRequestBytes": max_request_bytes,
+                        "apis": {"allow": api_allow or [], "deny": api_deny or []},
+                        "windowEventIntervalMs": window_event_interval_ms,
+                        "forwardKeys": forward_keys,
+                        "runtimeFlavor": runtime_flavor,
+                        "workerThreads": worker_threads,
+                    }
+                ),
+                "auth_token": runtime.auth_token,
+            },
             daemon=False,
         )
         proc.start()
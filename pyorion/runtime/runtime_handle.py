@@ -8,6 +8,8 @@ receive messages between Python and the Rust backend.
 import asyncio
 import json
 import traceback
+from contextvars import ContextVar
+from dataclasses import dataclass, field
 from typing import Any, Callable, Optional, TypeVar, cast
 
 from pydantic import BaseModel
@@ -45,36 +47,53 @@ class ApiResponseModel(BaseModel):
     code: int
     msg: str
     result: Any
+    label: str | None = None
 
     @classmethod
     def from_array(cls, arr: list[Any]) -> "ApiResponseModel":
         """Construct a response model from an array received from Rust.
 
-        :param arr: List of four elements: ``[id, code, msg, result]``.
+        :param arr: ``[id, code, msg, result, label]``; the trailing label of
+            the answering webview is optional.
         :type arr: list[Any]
         :return: Parsed response model.
         :rtype: ApiResponseModel
         :raises ValueError: If the array format is invalid.
         """
-        if not isinstance(arr, list) or len(arr) != 4:
+        if not isinstance(arr, list) or len(arr) not in (4, 5):
             raise ValueError(f"Invalid ApiResponse array: {arr}")
-        return cls(id=arr[0], code=arr[1], msg=arr[2], result=arr[3])
+        label = arr[4] if len(arr) == 5 else None
+        return cls(id=arr[0], code=arr[1], msg=arr[2], result=arr[3], label=label)
 
 
 class ApiError(Exception):
     """Error raised when the Rust event loop returns a non-zero status code."""
 
-    def __init__(self, code: int, msg: str) -> None:
+    def __init__(
+        self, code: int, msg: str, kind: str | None = None, detail: Any = None
+    ) -> None:
         """Initialize the API error.
 
         :param code: Numeric error code returned by Rust.
         :type code: int
         :param msg: Human-readable error message.
         :type msg: str
+        :param kind: Error category (e.g. ``notFound``, ``timeout``, ``invalidArgs``).
+        :type kind: str | None
+        :param detail: Optional structured detail attached by the handler.
+        :type detail: Any
         """
         super().__init__(f"[API-{code}] {msg}")
         self.code = code
         self.msg = msg
+        self.kind = kind
+        self.detail = detail
+
+    @classmethod
+    def from_response(cls, resp: "ApiResponseModel") -> "ApiError":
+        """Build an error from a response, using its structured payload if present."""
+        payload = resp.result if isinstance(resp.result, dict) else {}
+        return cls(resp.code, resp.msg, payload.get("kind"), payload.get("detail"))
 
 
 class PendingRegistry:
@@ -129,16 +148,57 @@ class PendingRegistry:
         self._pending.clear()
 
 
+@dataclass
+class RuntimeHandle:
+    """Connection settings and request queue of one launched WebFrame.
+
+    Every launch gets its own handle, so several WebFrames in one process
+    never send requests with each other's socket, token or codec.
+    """
+
+    pipe_name: str
+    auth_token: str | None = None
+    codec: str = "json"
+    queue: asyncio.Queue[dict[str, Any]] = field(default_factory=asyncio.Queue)
+
+
 _pending = PendingRegistry()
-task_queue: asyncio.Queue[dict[str, Any]] = asyncio.Queue()
+_current_runtime: ContextVar[RuntimeHandle | None] = ContextVar(
+    "pyorion_runtime", default=None
+)
+_latest_runtime: RuntimeHandle | None = None
+
+
+def activate_runtime(runtime: RuntimeHandle) -> None:
+    """Route requests made from the current context, and tasks it starts, to ``runtime``.
+
+    Calls from contexts that never saw an activation (e.g. tasks created
+    before the launch) go to the most recently activated runtime.
+    """
+    global _latest_runtime
+    _current_runtime.set(runtime)
+    _latest_runtime = runtime
+
+
+def current_runtime() -> RuntimeHandle:
+    """Return the runtime requests from the current context are sent to."""
+    runtime = _current_runtime.get() or _latest_runtime
+    if runtime is None:
+        raise RuntimeError("No PyOrion runtime has been launched")
+    return runtime
 
 
-async def send_loop_event(data: list[Any]) -> list[Any] | None:
-    """Send an event to the Rust event loop over the platform bridge."""
+async def send_loop_event(
+    data: list[Any], runtime: RuntimeHandle | None = None
+) -> list[Any] | None:
+    """Send an event to the Rust event loop of ``runtime`` over the platform bridge."""
+    runtime = runtime or current_runtime()
     try:
         response_str: Optional[Any] = await send_event_over_platform(
-            name="pyframe_pipe",
+            name=runtime.pipe_name,
             message=json.dumps(data),
+            token=runtime.auth_token,
+            codec=runtime.codec,
         )
 
         if response_str is None:
@@ -158,28 +218,28 @@ async def handle_event_loop_response(
     resp = ApiResponseModel.from_array(arr)
     if future:
         if resp.code != 0:
-            future.set_exception(ApiError(resp.code, resp.msg))
+            future.set_exception(ApiError.from_response(resp))
         else:
             future.set_result(resp.result)
     else:
         _pending.resolve(
             resp.id,
-            error=ApiError(resp.code, resp.msg) if resp.code != 0 else None,
+            error=ApiError.from_response(resp) if resp.code != 0 else None,
             result=None if resp.code != 0 else resp.result,
         )
 
 
-async def eventloop_sender() -> None:
-    """Continuous dispatcher loop for sending tasks to the Rust event loop."""
+async def eventloop_sender(runtime: RuntimeHandle) -> None:
+    """Continuous dispatcher loop for sending the tasks of ``runtime`` to its event loop."""
     try:
         while True:
-            task = await task_queue.get()
+            task = await runtime.queue.get()
             future: asyncio.Future[Any] | None = task.pop("future", None)
             data: list[Any] | None = task.get("data")
             if data is None:
                 return
             try:
-                arr = await send_loop_event(data)
+                arr = await send_loop_event(data, runtime)
                 if arr:
                     await handle_event_loop_response(arr, future=future)
             except Exception as exc:
@@ -231,12 +291,13 @@ async def event_register(
     :raises asyncio.TimeoutError: If no response is received in time.
     :raises Exception: For connection or serialization errors.
     """
+    runtime = current_runtime()
     req_id = _pending.next_id()
     request = ApiRequestModel(id=req_id, method=method, args=normalize_args(args))
     future: asyncio.Future[Any] = asyncio.get_event_loop().create_future()
     _pending.register(req_id, future)
 
-    await task_queue.put({"data": request.to_json_array(), "future": future})
+    await runtime.queue.put({"data": request.to_json_array(), "future": future})
 
     try:
         raw_result = await asyncio.wait_for(future, timeout=10.0)
@@ -5,7 +5,7 @@ from __future__ import annotations
 import base64
 from enum import Enum
 from pathlib import Path
-from typing import Any, ClassVar
+from typing import Any, ClassVar, Literal
 
 from pydantic import (
     BaseModel,
@@ -28,6 +28,46 @@ class BaseSchema(BaseModel):
     )
 
 
+class TlsConfig(BaseSchema):
+    """TLS settings for the frontend WebSocket connection.
+
+    The webview validates the backend certificate against the system trust
+    store. Settings the platform's webview cannot apply fail at startup.
+
+    Fields:
+    - enabled (bool): Upgrade the connection to ``wss://``
+    - ca_cert (Path, optional): PEM certificate the backend presents, trusted
+      on top of the system store (Linux only)
+    - insecure_skip_verify (bool): Accept any certificate (Linux and Windows)
+    - allow_insecure (bool): Explicit opt-in required for ``insecure_skip_verify``
+    """
+
+    model_config = ConfigDict(extra="forbid")
+
+    enabled: bool = Field(default=False, description="Enable TLS (wss://).")
+    ca_cert: Path | None = Field(
+        default=None, description="PEM file with the backend's certificate."
+    )
+    insecure_skip_verify: bool = Field(
+        default=False, description="Skip certificate verification (unsafe)."
+    )
+    allow_insecure: bool = Field(
+        default=False, description="Opt-in required for insecure_skip_verify."
+    )
+
+    @model_validator(mode="after")
+    def insecure_needs_opt_in(self) -> "TlsConfig":
+        """Reject ``insecure_skip_verify`` without ``allow_insecure``.
+
+        :return: The validated settings.
+        :rtype: TlsConfig
+        :raises ValueError: If verification is skipped without the opt-in.
+        """
+        if self.insecure_skip_verify and not self.allow_insecure:
+            raise ValueError("insecure_skip_verify needs allow_insecure=True as well")
+        return self
+
+
 class WebSocketConfig(BaseSchema):
     """WebSocketConfig - Configuration model for PyOrionConnections.
 
@@ -36,6 +76,13 @@ class WebSocketConfig(BaseSchema):
     - protocols (list[str], optional): Subprotocols for the handshake
     - auto_reconnect (bool, optional): Enable automatic reconnect
     - reconnect_interval (int, optional): Reconnect delay in milliseconds
+    - reconnect_max_interval (int, optional): Upper bound for the backoff delay
+    - reconnect_backoff (float, optional): Delay multiplier per failed attempt
+    - max_reconnect_attempts (int, optional): Attempts before giving up
+    - heartbeat_interval (int, optional): Milliseconds between pings, 0 disables
+    - heartbeat_timeout (int, optional): Milliseconds to wait for the pong
+    - tls (TlsConfig, optional): TLS settings for wss:// connections
+    - global_name (str, optional): ``window`` property holding the connection
     """
 
     url: str = Field(
@@ -50,6 +97,30 @@ class WebSocketConfig(BaseSchema):
     reconnect_interval: int | None = Field(
         default=3000, description="Reconnect interval in milliseconds."
     )
+    reconnect_max_interval: int | None = Field(
+        default=None, description="Maximum reconnect delay in milliseconds."
+    )
+    reconnect_backoff: float | None = Field(
+        default=None, description="Multiplier applied to the delay after each attempt."
+    )
+    max_reconnect_attempts: int | None = Field(
+        default=None, description="Reconnect attempts before giving up (unlimited if unset)."
+    )
+    heartbeat_interval: int | None = Field(
+        default=None, description="Milliseconds between heartbeat pings; 0 disables them."
+    )
+    heartbeat_timeout: int | None = Field(
+        default=None, description="Milliseconds to wait for a pong before the peer is lost."
+    )
+    tls: TlsConfig | None = Field(
+        default=None, description="Optional TLS settings for wss:// connections."
+    )
+    global_name: str | None = Field(
+        default=None,
+        pattern=r"^[A-Za-z_$][A-Za-z0-9_$]*$",
+        description="Name of the window property holding the connection "
+        "(default PyOrionConnections).",
+    )
 
     class Config:
         """Validate example configuration"""
@@ -256,16 +327,29 @@ class Monitor(BaseSchema):
     video_modes: list[MonitorVideoMode]
 
 
+class AssetsOptions(BaseSchema):
+    """Source of the frontend served over ``app://`` (``dir`` or ``bundle``)."""
+
+    dir: Path | str | None = None
+    bundle: Path | str | None = None
+    index: str | None = None
+
+
 class WebViewOptions(BaseSchema):
     """Optionen zur Konfiguration eines WebViews."""
 
     label: str | None = None
     render_protocol: Path | str | None = None
+    assets: AssetsOptions | None = None
+    url: str | None = None
+    html: str | None = None
     transparent: bool | None = None
     visible: bool | None = None
     devtools: bool | None = None
     incognito: bool | None = None
     initialization_script: str | None = None
+    invoke_helper: bool | None = None
+    initialization_scripts: list[str] | None = None
     accept_first_mouse: bool | None = None
     autoplay: bool | None = None
     focused: bool | None = None
@@ -274,10 +358,30 @@ class WebViewOptions(BaseSchema):
     background_color: RGBA | None = None
     bounds: WebViewBounds | None = None
     headers: dict[str, str] | None = None
+    asset_headers: dict[str, str] | None = None
     proxy_config: str | None = None
+    navigation_allowlist: list[str] | None = None
+    navigation_confirm_timeout_ms: int | None = None
+    data_directory: Path | str | None = None
+    file_drop: bool | None = None
+    enable_drag_regions: bool | None = None
     zoom_hotkeys: bool | None = None
     background_throttling: bool | None = None
     back_forward_navigation_gestures: bool | None = None
+    webview2_args: list[str] | None = None
+    webview2_data_dir: Path | str | None = None
+
+    @model_validator(mode="after")
+    def single_start_page(self) -> "WebViewOptions":
+        """Reject setting both ``url`` and ``html`` as the first page.
+
+        :return: The validated options.
+        :rtype: WebViewOptions
+        :raises ValueError: If both are set.
+        """
+        if self.url is not None and self.html is not None:
+            raise ValueError("url and html cannot both be set")
+        return self
 
 
 class WindowOptions(BaseSchema):
@@ -306,6 +410,19 @@ class WindowOptions(BaseSchema):
     visible: bool | None = None
     visible_on_all_workspaces: bool | None = None
     window_icon: Icon | None = None
+    titlebar_transparent: bool | None = None
+    full_size_content_view: bool | None = None
+    title_hidden: bool | None = None
+    traffic_light_inset: Position | None = None
+    skip_taskbar: bool | None = None
+    owner_window: int | None = None
+    parent_window: int | None = None
+    taskbar_icon: Icon | None = None
+    app_id: str | None = None
+    double_buffered: bool | None = None
+    transparent_draw: bool | None = None
+    webkit_transparency_workaround: bool | None = None
+    focus_webview_on_show: bool | None = None
     webview: WebViewOptions | None = None
 
 
@@ -393,6 +510,29 @@ class ClipboardImage(BaseSchema):
         return v
 
 
+class ClipboardSetImageResult(BaseSchema):
+    """Outcome of copying an image to the clipboard.
+
+    ``error`` names the failure when ``ok`` is false: ``invalidBase64``,
+    ``dimensionMismatch`` (data is not ``width * height * 4`` RGBA bytes),
+    ``clipboardLocked``, ``notSupported`` (the clipboard backend cannot hold
+    images) or ``backendError``.
+    """
+
+    ok: bool = Field(..., description="Whether the image was copied.")
+    error: (
+        Literal[
+            "invalidBase64",
+            "dimensionMismatch",
+            "clipboardLocked",
+            "notSupported",
+            "backendError",
+        ]
+        | None
+    ) = Field(None, description="Failure category when ``ok`` is false.")
+    message: str | None = Field(None, description="Details of the failure.")
+
+
 class WindowEffect(str, Enum):
     """Window visual effect types.
 
@@ -1,7 +1,7 @@
 // Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
-use crate::api_manager::ApiManager;
+use crate::api_manager::{ApiManager, Deferred, ErrorKind, RequestContext};
 use arboard::{Clipboard, ImageData};
 use once_cell::sync::Lazy;
 use pyorion_macros::api;
@@ -20,6 +20,11 @@ pub fn clipboard_api(api: &mut ApiManager) {
     api.register_api("clipboard.clear", clipboard_clear);
     api.register_api("clipboard.set_image", clipboard_set_image);
     api.register_api("clipboard.get_image", clipboard_get_image);
+    api.register_api("clipboard.getImagePng", clipboard_get_image_png);
+    api.register_api("clipboard.hasText", clipboard_has_text);
+    api.register_api("clipboard.hasImage", clipboard_has_image);
+    api.register_api("clipboard.startMonitoring", clipboard_start_monitoring);
+    api.register_api("clipboard.stopMonitoring", clipboard_stop_monitoring);
 }
 
 // Globale Clipboard-Instanz
@@ -31,6 +36,39 @@ pub static CLIPBOARD: Lazy<Mutex<Clipboard>> = Lazy::new(|| {
         .into()
 });
 
+/// Whether the clipboard backend handles images, probed on first use.
+///
+/// Windows, macOS and X11 support images. On Wayland they work when the
+/// compositor offers the data-control protocol or through XWayland; other
+/// setups report the clipboard as unsupported or panic inside the backend.
+/// This single probe is the only place such a panic is caught, so the image
+/// APIs can fail cleanly afterwards instead of guarding every call.
+static IMAGE_SUPPORT: Lazy<bool> = Lazy::new(|| {
+    let Ok(mut cb) = CLIPBOARD.lock() else {
+        return false;
+    };
+    match panic::catch_unwind(panic::AssertUnwindSafe(|| cb.get_image())) {
+        Ok(Err(arboard::Error::ClipboardNotSupported)) | Err(_) => {
+            tracing::warn!("image clipboard not supported on this backend");
+            false
+        }
+        Ok(_) => true,
+    }
+});
+
+/// Fails with `501` when the backend cannot handle images.
+fn require_image_support() -> Result<()> {
+    if *IMAGE_SUPPORT {
+        Ok(())
+    } else {
+        Err(crate::api_manager::ApiError::new(
+            ErrorKind::NotSupported,
+            "image clipboard not supported on this backend",
+        )
+        .into())
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClipboardImage {
@@ -66,16 +104,90 @@ fn clipboard_clear() -> Result<()> {
     Ok(cb.clear()?)
 }
 
+/// Why `clipboard.set_image` could not copy an image.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SetImageError {
+    /// The image data is not valid base64.
+    InvalidBase64,
+    /// The data is not `width * height * 4` bytes of RGBA.
+    DimensionMismatch,
+    /// The clipboard lock was poisoned by an earlier panic.
+    ClipboardLocked,
+    /// The clipboard backend cannot hold images.
+    NotSupported,
+    /// The platform clipboard refused the image or panicked.
+    BackendError,
+}
+
+/// Outcome of `clipboard.set_image`: `error` and `message` are set when
+/// `ok` is `false`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SetImageResult {
+    pub ok: bool,
+    pub error: Option<SetImageError>,
+    pub message: Option<String>,
+}
+
+impl SetImageResult {
+    fn ok() -> Self {
+        Self {
+            ok: true,
+            error: None,
+            message: None,
+        }
+    }
+
+    fn failed(error: SetImageError, message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(error),
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// Copies an RGBA image to the clipboard.
+///
+/// Failures are reported in the result rather than as errors, so Python can
+/// tell a bad argument from a clipboard problem.
 #[api]
-fn clipboard_set_image(width: usize, height: usize, b64_bytes: String) -> Result<bool> {
-    let bytes = match general_purpose::STANDARD.decode(&b64_bytes) {
+fn clipboard_set_image(width: usize, height: usize, b64_bytes: String) -> Result<SetImageResult> {
+    Ok(set_image(width, height, &b64_bytes))
+}
+
+/// Copies base64 encoded RGBA pixels to the clipboard.
+fn set_image(width: usize, height: usize, b64_bytes: &str) -> SetImageResult {
+    let bytes = match general_purpose::STANDARD.decode(b64_bytes) {
         Ok(b) => b,
-        Err(_) => return Ok(false),
+        Err(e) => return SetImageResult::failed(SetImageError::InvalidBase64, e.to_string()),
     };
 
+    let expected = width.checked_mul(height).and_then(|n| n.checked_mul(4));
+    if expected != Some(bytes.len()) {
+        return SetImageResult::failed(
+            SetImageError::DimensionMismatch,
+            format!(
+                "{}x{} RGBA needs {} bytes, got {}",
+                width,
+                height,
+                expected.map_or_else(|| "too many".to_string(), |n| n.to_string()),
+                bytes.len()
+            ),
+        );
+    }
+
+    if !*IMAGE_SUPPORT {
+        return SetImageResult::failed(
+            SetImageError::NotSupported,
+            "image clipboard not supported on this backend",
+        );
+    }
+
     let mut cb = match CLIPBOARD.lock() {
         Ok(c) => c,
-        Err(_) => return Ok(false),
+        Err(e) => return SetImageResult::failed(SetImageError::ClipboardLocked, e.to_string()),
     };
 
     let img = ImageData {
@@ -84,35 +196,259 @@ fn clipboard_set_image(width: usize, height: usize, b64_bytes: String) -> Result
         bytes: std::borrow::Cow::Owned(bytes),
     };
 
-    // Panics und normale Fehler auf bool mappen
-    match panic::catch_unwind(panic::AssertUnwindSafe(|| cb.set_image(img))) {
-        Ok(Ok(())) => Ok(true), // alles gut
-        _ => Ok(false),         // Fehler oder Panic
+    match cb.set_image(img) {
+        Ok(()) => SetImageResult::ok(),
+        Err(e) => SetImageResult::failed(SetImageError::BackendError, e.to_string()),
     }
 }
 
-#[api]
-fn clipboard_get_image() -> Result<ClipboardImage> {
+/// Reads the clipboard image as raw RGBA.
+fn read_image() -> Result<ImageData<'static>> {
+    require_image_support()?;
     let mut cb = CLIPBOARD
         .lock()
         .map_err(|_| anyhow::anyhow!("Clipboard Lock Error"))?;
-    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| cb.get_image()));
 
-    match result {
-        Ok(Ok(img)) => {
-            let b64 = general_purpose::STANDARD.encode(img.bytes.as_ref());
-            Ok(ClipboardImage {
-                width: img.width,
-                height: img.height,
-                data: b64,
-            })
-        }
-        Ok(Err(e)) => Err(anyhow::anyhow!(
+    match cb.get_image() {
+        Ok(img) => Ok(img.to_owned_img()),
+        Err(e) => Err(anyhow::anyhow!(
             "Clipboard: Bild konnte nicht gelesen werden: {}",
             e
         )),
-        Err(_) => Err(anyhow::anyhow!(
-            "Clipboard: interner Panic beim Lesen des Bildes"
-        )),
+    }
+}
+
+#[api]
+fn clipboard_get_image() -> Result<ClipboardImage> {
+    let img = read_image()?;
+    let b64 = general_purpose::STANDARD.encode(img.bytes.as_ref());
+    Ok(ClipboardImage {
+        width: img.width,
+        height: img.height,
+        data: b64,
+    })
+}
+
+/// Encodes `width` x `height` RGBA pixels as PNG.
+fn encode_png(width: usize, height: usize, rgba: Vec<u8>) -> Result<Vec<u8>> {
+    let rgba = image::RgbaImage::from_raw(width as u32, height as u32, rgba)
+        .ok_or_else(|| anyhow::anyhow!("Clipboard: Bilddaten passen nicht zur Bildgröße"))?;
+    let mut png = Vec::new();
+    rgba.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)?;
+    Ok(png)
+}
+
+/// Reads the clipboard image and encodes it as base64 PNG, reporting each
+/// step through `ctx`.
+fn encode_image_png(ctx: &RequestContext) -> Result<String> {
+    ctx.progress(0, Some("reading clipboard"));
+    let img = read_image()?;
+
+    ctx.progress(33, Some("encoding png"));
+    let png = encode_png(img.width, img.height, img.bytes.into_owned())?;
+    ctx.progress(66, Some("encoding base64"));
+    let encoded = general_purpose::STANDARD.encode(png);
+    ctx.progress(100, None);
+    Ok(encoded)
+}
+
+/// Returns the clipboard image as a base64 encoded PNG.
+///
+/// Unlike `clipboard.get_image` the result carries its own format and size,
+/// so Python can hand it straight to `PIL.Image.open`. Large images take a
+/// while to encode, so the work runs off the event loop and emits
+/// `progress` events before the answer arrives.
+#[api]
+fn clipboard_get_image_png() -> Result<()> {
+    let Some(responder) = app.responder(req.0) else {
+        return Ok(());
+    };
+    let label = app.label().to_string();
+    app.rt.spawn_blocking(move || {
+        let resp = match encode_image_png(&ctx) {
+            Ok(png) => req.ok(png),
+            Err(e) => req.err(ErrorKind::Handler.code(), e.to_string()),
+        };
+        let _ = responder.send(resp.with_label(&label));
+    });
+    Err(Deferred.into())
+}
+
+/// Maps a clipboard read to availability: empty or other formats are `false`.
+fn available<T>(result: Result<T, arboard::Error>) -> Result<bool> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(arboard::Error::ContentNotAvailable) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether the clipboard currently holds text.
+#[api]
+fn clipboard_has_text() -> Result<bool> {
+    let mut cb = CLIPBOARD
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Clipboard Lock Error"))?;
+    available(cb.get_text())
+}
+
+/// Whether the clipboard currently holds an image.
+///
+/// arboard has no format query, so this reads and converts the full image;
+/// expect it to cost as much as `clipboard.get_image` for large images.
+/// Always `false` where the backend cannot hold images.
+#[api]
+fn clipboard_has_image() -> Result<bool> {
+    if !*IMAGE_SUPPORT {
+        return Ok(false);
+    }
+    let mut cb = CLIPBOARD
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Clipboard Lock Error"))?;
+    available(cb.get_image())
+}
+
+/// Default poll interval of the clipboard monitor in milliseconds.
+const DEFAULT_MONITOR_INTERVAL_MS: u64 = 500;
+
+/// Running clipboard monitor thread.
+struct ClipboardMonitor {
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    handle: std::thread::JoinHandle<()>,
+}
+
+static MONITOR: Lazy<Mutex<Option<ClipboardMonitor>>> = Lazy::new(|| Mutex::new(None));
+
+/// Cheap fingerprint of the clipboard content used to detect changes.
+fn fingerprint(cb: &mut Clipboard) -> (&'static str, u64) {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    if let Ok(text) = cb.get_text() {
+        text.hash(&mut hasher);
+        return ("text", hasher.finish());
+    }
+    if !*IMAGE_SUPPORT {
+        return ("empty", 0);
+    }
+    if let Ok(img) = cb.get_image() {
+        (img.width, img.height, img.bytes.as_ref()).hash(&mut hasher);
+        return ("image", hasher.finish());
+    }
+    ("empty", 0)
+}
+
+/// Stops the clipboard monitor, if any, and waits for its thread to exit.
+pub(crate) fn stop_monitor() {
+    let monitor = MONITOR.lock().ok().and_then(|mut m| m.take());
+    if let Some(monitor) = monitor {
+        monitor
+            .stop
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        let _ = monitor.handle.join();
+    }
+}
+
+/// Starts polling the clipboard and emits `clipboardChanged` with the new
+/// `contentType` (`text`, `image` or `empty`) whenever it changes.
+///
+/// Calling it again restarts the monitor with the new interval. No platform
+/// offers a portable change notification, so this polls on its own thread
+/// with its own clipboard handle.
+#[api]
+fn clipboard_start_monitoring(interval_ms: Option<u64>) -> Result<bool> {
+    stop_monitor();
+
+    let interval = std::time::Duration::from_millis(
+        interval_ms.unwrap_or(DEFAULT_MONITOR_INTERVAL_MS).max(50),
+    );
+    let stop = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let proxy = app.proxy.clone();
+    let mut cb = Clipboard::new()?;
+
+    let handle = std::thread::spawn({
+        let stop = stop.clone();
+        move || {
+            let mut last = fingerprint(&mut cb);
+            while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                std::thread::sleep(interval);
+                let current = fingerprint(&mut cb);
+                if current != last {
+                    last = current;
+                    let _ = proxy.send_event(crate::utils::UserEvent::Emit(
+                        "clipboardChanged".to_string(),
+                        serde_json::json!({ "contentType": current.0 }),
+                    ));
+                }
+            }
+        }
+    });
+
+    *MONITOR
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Clipboard Lock Error"))? =
+        Some(ClipboardMonitor { stop, handle });
+    Ok(true)
+}
+
+/// Stops the monitor started by `clipboard.startMonitoring`.
+#[api]
+fn clipboard_stop_monitoring() -> Result<bool> {
+    stop_monitor();
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_round_trip_keeps_dimensions() {
+        let (width, height) = (3, 2);
+        let rgba: Vec<u8> = (0..width * height * 4).map(|i| i as u8).collect();
+
+        let png = encode_png(width, height, rgba.clone()).unwrap();
+        let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .unwrap()
+            .into_rgba8();
+
+        assert_eq!(decoded.dimensions(), (width as u32, height as u32));
+        assert_eq!(decoded.into_raw(), rgba);
+    }
+
+    #[test]
+    fn png_rejects_short_pixel_data() {
+        assert!(encode_png(3, 2, vec![0; 3 * 2 * 4 - 1]).is_err());
+    }
+
+    struct Discard;
+
+    impl crate::api_manager::EventSink for Discard {
+        fn send(&self, _event: crate::utils::UserEvent) {}
+    }
+
+    #[test]
+    #[ignore = "needs a desktop clipboard and overwrites its contents"]
+    fn image_round_trips_through_the_clipboard() {
+        if !*IMAGE_SUPPORT {
+            return;
+        }
+        let (width, height) = (4, 3);
+        let rgba: Vec<u8> = (0..width * height * 4).map(|i| (i * 7) as u8).collect();
+
+        let result = set_image(width, height, &general_purpose::STANDARD.encode(&rgba));
+        assert!(result.ok, "{:?}", result.message);
+
+        let req: crate::api_manager::ApiRequest =
+            serde_json::from_str(r#"[1, "clipboard.get_image_png", []]"#).unwrap();
+        let ctx = RequestContext::detached(&req, Discard);
+        let png = general_purpose::STANDARD
+            .decode(encode_image_png(&ctx).unwrap())
+            .unwrap();
+        let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png)
+            .unwrap()
+            .into_rgba8();
+        assert_eq!(decoded.dimensions(), (width as u32, height as u32));
+        assert_eq!(decoded.into_raw(), rgba);
     }
 }
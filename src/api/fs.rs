@@ -0,0 +1,447 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::api_manager::{ApiError, ApiManager, Binary, Deferred, ErrorKind};
+use anyhow::Result;
+use base64::engine::general_purpose;
+use base64::Engine as _;
+use pyorion_macros::api;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub fn fs_api(api_manager: &mut ApiManager) {
+    api_manager.register_api("fs.readFile", read_file);
+    api_manager.register_api("fs.readFileBinary", read_file_binary);
+    api_manager.register_api("fs.readFilesZipped", read_files_zipped);
+    api_manager.register_api("fs.writeFile", write_file);
+    api_manager.register_api("fs.readDir", read_dir);
+    api_manager.register_api("fs.exists", exists);
+    api_manager.register_api("fs.mkdir", mkdir);
+    api_manager.register_api("fs.remove", remove);
+}
+
+/// Encoding of file contents on the wire.
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+enum FsEncoding {
+    #[default]
+    Utf8,
+    Base64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FsEntry {
+    name: String,
+    path: PathBuf,
+    is_file: bool,
+    is_dir: bool,
+    is_symlink: bool,
+}
+
+fn forbidden(path: &str) -> anyhow::Error {
+    ApiError::new(
+        ErrorKind::Forbidden,
+        format!("path outside of allowed roots: {}", path),
+    )
+    .into()
+}
+
+fn io_error(err: std::io::Error) -> anyhow::Error {
+    match err.kind() {
+        std::io::ErrorKind::NotFound => ApiError::new(ErrorKind::NotFound, err.to_string()).into(),
+        std::io::ErrorKind::PermissionDenied => {
+            ApiError::new(ErrorKind::Forbidden, err.to_string()).into()
+        }
+        _ => err.into(),
+    }
+}
+
+/// Canonicalizes `path`, resolving the longest existing ancestor for paths
+/// that do not exist yet (e.g. the target of `writeFile`).
+fn canonicalize_lenient(path: &Path) -> std::io::Result<PathBuf> {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        match existing.canonicalize() {
+            Ok(base) => return Ok(missing.iter().rev().fold(base, |acc, part| acc.join(part))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                let (Some(parent), Some(name)) = (existing.parent(), existing.file_name()) else {
+                    return Err(err);
+                };
+                missing.push(name.to_owned());
+                existing = parent;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Maps a requested path into the sandbox.
+///
+/// Relative paths are taken relative to the first root. `..` may not climb
+/// above the root and symlinks are followed before the containment check, so
+/// neither can be used to escape.
+pub(crate) fn resolve(roots: &[PathBuf], path: &str) -> Result<PathBuf> {
+    let Some(first) = roots.first() else {
+        return Err(ApiError::new(ErrorKind::Forbidden, "no filesystem roots configured").into());
+    };
+
+    let requested = Path::new(path);
+    let joined = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        first.join(requested)
+    };
+
+    // Lexical pass first so `root/../etc` never reaches the filesystem
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(forbidden(path));
+                }
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+
+    let resolved = canonicalize_lenient(&normalized).map_err(io_error)?;
+    let inside = roots.iter().any(|root| {
+        root.canonicalize()
+            .is_ok_and(|root| resolved.starts_with(root))
+    });
+    if inside {
+        Ok(resolved)
+    } else {
+        Err(forbidden(path))
+    }
+}
+
+#[api]
+fn read_file(path: String, encoding: Option<FsEncoding>) -> Result<String> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    let bytes = fs::read(path).map_err(io_error)?;
+    Ok(match encoding.unwrap_or_default() {
+        FsEncoding::Utf8 => String::from_utf8(bytes)?,
+        FsEncoding::Base64 => general_purpose::STANDARD.encode(bytes),
+    })
+}
+
+/// Like `fs.readFile`, but the contents travel as raw bytes to clients that
+/// negotiated binary parts.
+#[api]
+fn read_file_binary(path: String) -> Result<Binary> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    Ok(Binary(fs::read(path).map_err(io_error)?))
+}
+
+/// Selections adding up to more than this are zipped into a temporary file
+/// instead of memory, so only the finished archive is ever held at once.
+const ZIP_IN_MEMORY_LIMIT: u64 = 32 * 1024 * 1024;
+
+/// Largest archive `fs.readFilesZipped` returns.
+///
+/// The archive goes out as one binary part held in memory, so selections
+/// adding up to more are refused with `413` before zipping starts, and an
+/// archive that still ends up larger is refused before it is read back.
+const MAX_ARCHIVE_BYTES: u64 = 256 * 1024 * 1024;
+
+fn archive_too_large(size: u64, max: u64) -> anyhow::Error {
+    ApiError::new(ErrorKind::PayloadTooLarge, "archive too large")
+        .detail(serde_json::json!({ "size": size, "maxArchiveBytes": max }))
+        .into()
+}
+
+/// Removes a temporary file once dropped.
+struct TempFile(PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Archive entry name for `path`: its file name, numbered like `a (2).txt`
+/// when an earlier file already took it.
+fn entry_name(path: &Path, taken: &mut std::collections::HashSet<String>) -> String {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "file".to_string());
+    let mut candidate = name.clone();
+    let mut n = 1;
+    while !taken.insert(candidate.clone()) {
+        n += 1;
+        let stem = Path::new(&name).file_stem().map(|s| s.to_string_lossy());
+        candidate = match (stem, Path::new(&name).extension()) {
+            (Some(stem), Some(ext)) => format!("{} ({}).{}", stem, n, ext.to_string_lossy()),
+            _ => format!("{} ({})", name, n),
+        };
+    }
+    candidate
+}
+
+/// Writes `files` as a deflated ZIP into `writer`, copying each file through
+/// without reading it into memory first.
+fn zip_files<W: std::io::Write + std::io::Seek>(writer: W, files: &[PathBuf]) -> Result<W> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    let mut taken = std::collections::HashSet::new();
+    for path in files {
+        zip.start_file(entry_name(path, &mut taken), options)?;
+        let mut file = fs::File::open(path).map_err(io_error)?;
+        std::io::copy(&mut file, &mut zip)?;
+    }
+    Ok(zip.finish()?)
+}
+
+/// Zips `files` in memory, or through a temporary file for large selections,
+/// and returns the archive; one larger than `max` bytes fails with `413`
+/// without being read.
+fn zip_archive(files: &[PathBuf], total: u64, max: u64) -> Result<Vec<u8>> {
+    use std::io::{Read, Seek};
+
+    if total <= ZIP_IN_MEMORY_LIMIT {
+        let archive = zip_files(std::io::Cursor::new(Vec::new()), files)?.into_inner();
+        if archive.len() as u64 > max {
+            return Err(archive_too_large(archive.len() as u64, max));
+        }
+        return Ok(archive);
+    }
+    let temp = TempFile(std::env::temp_dir().join(format!("pyorion-{}.zip", uuid::Uuid::new_v4())));
+    let file = fs::File::options()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&temp.0)?;
+    let mut file = zip_files(file, files)?;
+    let size = file.metadata()?.len();
+    if size > max {
+        return Err(archive_too_large(size, max));
+    }
+    let mut archive = Vec::with_capacity(size as usize);
+    file.rewind()?;
+    file.read_to_end(&mut archive)?;
+    Ok(archive)
+}
+
+/// Packs the files at `paths` into one ZIP archive, returned as binary.
+///
+/// Saves a round trip per file, e.g. when exporting a selection. Every path
+/// must lie inside the filesystem roots and name a file; entries are named
+/// after the files, numbered on collisions. Zipping runs off the event loop.
+/// Selections and archives over 256 MiB are refused with `413`.
+#[api]
+fn read_files_zipped(paths: Vec<String>) -> Result<()> {
+    let roots = app.runtime_options.fs_roots();
+    let mut files = Vec::with_capacity(paths.len());
+    let mut total = 0u64;
+    for path in &paths {
+        let resolved = resolve(roots, path)?;
+        let meta = fs::metadata(&resolved).map_err(io_error)?;
+        if !meta.is_file() {
+            return Err(ApiError::invalid_args(format!("not a file: {}", path)).into());
+        }
+        total += meta.len();
+        files.push(resolved);
+    }
+    if total > MAX_ARCHIVE_BYTES {
+        return Err(archive_too_large(total, MAX_ARCHIVE_BYTES));
+    }
+
+    let Some(responder) = app.responder(req.0) else {
+        return Ok(());
+    };
+    let label = app.label().to_string();
+    app.rt.spawn_blocking(move || {
+        let resp = match zip_archive(&files, total, MAX_ARCHIVE_BYTES) {
+            Ok(archive) => req.binary(archive),
+            Err(err) => match err.downcast::<ApiError>() {
+                Ok(api_err) => req.fail(api_err),
+                Err(err) => req.err(ErrorKind::Handler.code(), err.to_string()),
+            },
+        };
+        let _ = responder.send(resp.with_label(&label));
+    });
+    Err(Deferred.into())
+}
+
+#[api]
+fn write_file(path: String, contents: String, encoding: Option<FsEncoding>) -> Result<bool> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    let bytes = match encoding.unwrap_or_default() {
+        FsEncoding::Utf8 => contents.into_bytes(),
+        FsEncoding::Base64 => general_purpose::STANDARD
+            .decode(contents)
+            .map_err(ApiError::invalid_args)?,
+    };
+    fs::write(path, bytes).map_err(io_error)?;
+    Ok(true)
+}
+
+#[api]
+fn read_dir(path: String) -> Result<Vec<FsEntry>> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(path).map_err(io_error)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        entries.push(FsEntry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            path: entry.path(),
+            is_file: file_type.is_file(),
+            is_dir: file_type.is_dir(),
+            is_symlink: file_type.is_symlink(),
+        });
+    }
+    Ok(entries)
+}
+
+#[api]
+fn exists(path: String) -> Result<bool> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    Ok(path.exists())
+}
+
+#[api]
+fn mkdir(path: String, recursive: Option<bool>) -> Result<bool> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    if recursive.unwrap_or(false) {
+        fs::create_dir_all(path).map_err(io_error)?;
+    } else {
+        fs::create_dir(path).map_err(io_error)?;
+    }
+    Ok(true)
+}
+
+#[api]
+fn remove(path: String, recursive: Option<bool>) -> Result<bool> {
+    let path = resolve(app.runtime_options.fs_roots(), &path)?;
+    if path.is_dir() {
+        if recursive.unwrap_or(false) {
+            fs::remove_dir_all(path).map_err(io_error)?;
+        } else {
+            fs::remove_dir(path).map_err(io_error)?;
+        }
+    } else {
+        fs::remove_file(path).map_err(io_error)?;
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forbidden_code(result: Result<PathBuf>) -> i32 {
+        result.unwrap_err().downcast::<ApiError>().unwrap().code
+    }
+
+    #[test]
+    fn resolve_stays_inside_the_root() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("notes.txt"), "hi").unwrap();
+        let roots = [root.path().to_path_buf()];
+
+        let resolved = resolve(&roots, "notes.txt").unwrap();
+        assert_eq!(
+            resolved,
+            root.path().canonicalize().unwrap().join("notes.txt")
+        );
+        // Not created yet, still inside
+        assert!(resolve(&roots, "new/file.txt").is_ok());
+        assert!(resolve(&roots, "sub/../notes.txt").is_ok());
+    }
+
+    #[test]
+    fn resolve_rejects_parent_dir_escapes() {
+        let root = tempfile::tempdir().unwrap();
+        let roots = [root.path().to_path_buf()];
+
+        assert_eq!(forbidden_code(resolve(&roots, "../outside.txt")), 403);
+        assert_eq!(
+            forbidden_code(resolve(&roots, "sub/../../outside.txt")),
+            403
+        );
+    }
+
+    #[test]
+    fn resolve_checks_absolute_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let roots = [root.path().to_path_buf()];
+
+        let inside = root.path().join("notes.txt");
+        assert!(resolve(&roots, inside.to_str().unwrap()).is_ok());
+        let outside = other.path().join("notes.txt");
+        assert_eq!(
+            forbidden_code(resolve(&roots, outside.to_str().unwrap())),
+            403
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_follows_symlinks_before_checking() {
+        let root = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        fs::write(other.path().join("secret.txt"), "no").unwrap();
+        std::os::unix::fs::symlink(other.path(), root.path().join("link")).unwrap();
+        let roots = [root.path().to_path_buf()];
+
+        assert_eq!(forbidden_code(resolve(&roots, "link/secret.txt")), 403);
+        assert_eq!(forbidden_code(resolve(&roots, "link/new.txt")), 403);
+    }
+
+    fn zip_fixture() -> (tempfile::TempDir, Vec<PathBuf>) {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("b")).unwrap();
+        let files = vec![dir.path().join("a.txt"), dir.path().join("b/a.txt")];
+        for (i, file) in files.iter().enumerate() {
+            fs::write(file, format!("file {}", i).repeat(100)).unwrap();
+        }
+        (dir, files)
+    }
+
+    #[test]
+    fn zip_archive_through_a_temp_file() {
+        let (_dir, files) = zip_fixture();
+        // Claiming a large selection takes the temp file path
+        let archive = zip_archive(&files, ZIP_IN_MEMORY_LIMIT + 1, MAX_ARCHIVE_BYTES).unwrap();
+
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(archive)).unwrap();
+        let mut names: Vec<_> = zip
+            .file_names()
+            .map(|name| name.unwrap().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["a (2).txt", "a.txt"]);
+        let mut contents = String::new();
+        std::io::Read::read_to_string(&mut zip.by_name("a (2).txt").unwrap(), &mut contents)
+            .unwrap();
+        assert_eq!(contents, "file 1".repeat(100));
+    }
+
+    #[test]
+    fn zip_archive_over_the_limit_is_refused() {
+        let (_dir, files) = zip_fixture();
+        for total in [0, ZIP_IN_MEMORY_LIMIT + 1] {
+            let err = zip_archive(&files, total, 64).unwrap_err();
+            let err = err.downcast::<ApiError>().unwrap();
+            assert_eq!(err.code, 413);
+            assert_eq!(err.detail.unwrap()["maxArchiveBytes"], 64);
+        }
+    }
+
+    #[test]
+    fn resolve_needs_a_root() {
+        assert_eq!(forbidden_code(resolve(&[], "notes.txt")), 403);
+    }
+}
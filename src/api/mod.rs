@@ -7,7 +7,9 @@ mod clipboard;
 mod control_center;
 mod dialog;
 mod dirs;
+mod fs;
 mod resource;
+mod shell;
 mod vibrancy;
 mod webview;
 mod window;
@@ -20,4 +22,11 @@ pub fn register_api_instances(api_manager: &mut ApiManager) {
     clipboard::clipboard_api(api_manager);
     dirs::dirs_api(api_manager);
     resource::resource_api(api_manager);
+    fs::fs_api(api_manager);
+    shell::shell_api(api_manager);
+}
+
+/// Stops background workers started by API handlers.
+pub fn shutdown() {
+    clipboard::stop_monitor();
 }
@@ -0,0 +1,63 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::api_manager::{ApiError, ApiManager, ErrorKind};
+use anyhow::Result;
+use pyorion_macros::api;
+
+pub fn shell_api(api_manager: &mut ApiManager) {
+    api_manager.register_api("shell.openUrl", open_url);
+    api_manager.register_api("shell.openPath", open_path);
+}
+
+/// Schemes `shell.openUrl` hands to the OS; anything else could launch
+/// arbitrary protocol handlers.
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+fn launch(target: &std::ffi::OsStr) -> Result<bool> {
+    open::that_detached(target).map_err(|err| {
+        ApiError::new(
+            ErrorKind::NotSupported,
+            format!("no handler available: {}", err),
+        )
+    })?;
+    Ok(true)
+}
+
+/// Opens `url` with the default browser or mail client.
+///
+/// Only `http`, `https` and `mailto` URLs are accepted.
+#[api]
+fn open_url(url: String) -> Result<bool> {
+    let parsed = url::Url::parse(&url).map_err(ApiError::invalid_args)?;
+    if !ALLOWED_SCHEMES.contains(&parsed.scheme()) {
+        return Err(ApiError::new(
+            ErrorKind::Forbidden,
+            format!("scheme not allowed: {}", parsed.scheme()),
+        )
+        .into());
+    }
+    launch(parsed.as_str().as_ref())
+}
+
+/// Opens `path` with the application registered for its type.
+///
+/// When `fs` roots are configured the path has to stay inside them.
+#[api]
+fn open_path(path: String) -> Result<bool> {
+    let roots = app.runtime_options.fs_roots();
+    let path = if roots.is_empty() {
+        std::path::PathBuf::from(path)
+    } else {
+        super::fs::resolve(roots, &path)?
+    };
+    if !path.exists() {
+        return Err(ApiError::new(
+            ErrorKind::NotFound,
+            format!("no such file: {}", path.display()),
+        )
+        .into());
+    }
+    launch(path.as_os_str())
+}
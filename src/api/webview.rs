@@ -5,30 +5,437 @@
 use anyhow::Result;
 use pyorion_macros::api;
 
-use crate::api_manager::ApiManager;
+use crate::api_manager::{ApiError, ApiManager, ApiRequest, ApiResponse, Deferred, ErrorKind};
 
 pub fn webview_api(api_manager: &mut ApiManager) {
     api_manager.register_api("webview.isDevtoolsOpen", is_devtools_open);
     api_manager.register_api("webview.openDevtools", open_devtools);
     api_manager.register_api("webview.closeDevtools", close_devtools);
+    api_manager.register_api("webview.setBounds", set_bounds);
+    api_manager.register_api("webview.setVisibility", set_visibility);
+    api_manager.register_api("webview.getUserAgent", get_user_agent);
+    api_manager.register_api("webview.setUserAgent", set_user_agent);
+    api_manager.register_api("webview.setProxy", set_proxy);
+    api_manager.register_api("webview.setNavigationAllowlist", set_navigation_allowlist);
+    api_manager.register_api("webview.resolveNavigation", resolve_navigation);
+    api_manager.register_api("webview.getConfig", get_config);
+    api_manager.register_api("webview.postMessage", post_message);
+    api_manager.register_api("webview.focus", focus);
+    api_manager.register_api("webview.reload", reload);
+    api_manager.register_api("webview.reloadIgnoringCache", reload_ignoring_cache);
+    api_manager.register_api("webview.getScrollPosition", get_scroll_position);
+    api_manager.register_api("webview.setScrollPosition", set_scroll_position);
 }
 
+/// What the handlers below need from a webview.
+///
+/// Implemented by [`wry::WebView`]; handlers that only go through it can be
+/// unit-tested against a fake, without a window or event loop.
+pub trait WebViewHandle {
+    fn is_devtools_open(&self) -> bool;
+    fn open_devtools(&self);
+    fn close_devtools(&self);
+    fn set_visible(&self, visible: bool) -> wry::Result<()>;
+    fn focus(&self) -> wry::Result<()>;
+    fn url(&self) -> wry::Result<String>;
+    fn reload(&self) -> wry::Result<()>;
+}
+
+impl WebViewHandle for wry::WebView {
+    fn is_devtools_open(&self) -> bool {
+        wry::WebView::is_devtools_open(self)
+    }
+    fn open_devtools(&self) {
+        wry::WebView::open_devtools(self)
+    }
+    fn close_devtools(&self) {
+        wry::WebView::close_devtools(self)
+    }
+    fn set_visible(&self, visible: bool) -> wry::Result<()> {
+        wry::WebView::set_visible(self, visible)
+    }
+    fn focus(&self) -> wry::Result<()> {
+        wry::WebView::focus(self)
+    }
+    fn url(&self) -> wry::Result<String> {
+        wry::WebView::url(self)
+    }
+    fn reload(&self) -> wry::Result<()> {
+        wry::WebView::reload(self)
+    }
+}
+
+/// Fails with `403` unless devtools are enabled for the webview, through
+/// `WebViewOptions.devtools` and the runtime `devtools` switch.
+fn ensure_devtools(config: &crate::window::builder::WebViewConfig) -> Result<()> {
+    if config.devtools {
+        Ok(())
+    } else {
+        Err(ApiError::new(ErrorKind::Forbidden, "devtools disabled").into())
+    }
+}
+
+/// Whether the devtools window is open; `403` when devtools are disabled.
 #[api]
 fn is_devtools_open() -> Result<bool> {
-    let webview = app.app_context()?.get_webview()?;
-    Ok(webview.is_devtools_open())
+    let ctx = app.app_context()?;
+    devtools_open(&*ctx.get_webview()?, &ctx.get_webview_config()?)
 }
 
+/// Opens the devtools window; `403` when devtools are disabled.
 #[api]
 fn open_devtools() -> Result<()> {
+    let ctx = app.app_context()?;
+    set_devtools_open(&*ctx.get_webview()?, &ctx.get_webview_config()?, true)
+}
+
+/// Closes the devtools window; `403` when devtools are disabled.
+#[api]
+fn close_devtools() -> Result<()> {
+    let ctx = app.app_context()?;
+    set_devtools_open(&*ctx.get_webview()?, &ctx.get_webview_config()?, false)
+}
+
+fn devtools_open(
+    webview: &impl WebViewHandle,
+    config: &crate::window::builder::WebViewConfig,
+) -> Result<bool> {
+    ensure_devtools(config)?;
+    Ok(webview.is_devtools_open())
+}
+
+fn set_devtools_open(
+    webview: &impl WebViewHandle,
+    config: &crate::window::builder::WebViewConfig,
+    open: bool,
+) -> Result<()> {
+    ensure_devtools(config)?;
+    if open {
+        webview.open_devtools();
+    } else {
+        webview.close_devtools();
+    }
+    Ok(())
+}
+
+/// Moves and resizes the webview inside its window.
+///
+/// Wrapper for [`wry::WebView::set_bounds`]; degenerate bounds are rejected
+/// with `422`.
+#[api]
+fn set_bounds(bounds: pyorion_options::window::WebViewBounds) -> Result<()> {
+    bounds
+        .validate()
+        .map_err(crate::api_manager::ApiError::invalid_args)?;
     let webview = app.app_context()?.get_webview()?;
-    webview.open_devtools();
+    webview.set_bounds(bounds.into())?;
     Ok(())
 }
 
+/// Shows or hides the webview without touching its window.
+///
+/// Wrapper for [`wry::WebView::set_visible`].
 #[api]
-fn close_devtools() -> Result<()> {
+fn set_visibility(visible: bool) -> Result<()> {
+    WebViewHandle::set_visible(&*app.app_context()?.get_webview()?, visible)?;
+    Ok(())
+}
+
+/// Returns the effective user agent, including the engine string the
+/// webview appends.
+///
+/// Read from `navigator.userAgent` in the page, so the answer arrives once
+/// the script callback fires; a page that never finishes loading runs into
+/// the request timeout.
+#[api]
+fn get_user_agent() -> Result<()> {
+    evaluate_deferred(&app, &req, "navigator.userAgent", |req, ua| {
+        req.ok(serde_json::from_str::<String>(&ua).unwrap_or(ua))
+    })
+}
+
+/// Moves keyboard focus into the webview, e.g. after a native dialog took it.
+///
+/// Wrapper for [`wry::WebView::focus`].
+#[api]
+fn focus() -> Result<bool> {
+    WebViewHandle::focus(&*app.app_context()?.get_webview()?)?;
+    Ok(true)
+}
+
+/// Reloads the current page and returns its URL.
+///
+/// Wrapper for [`wry::WebView::reload`].
+#[api]
+fn reload() -> Result<String> {
+    reload_page(&*app.app_context()?.get_webview()?)
+}
+
+fn reload_page(webview: &impl WebViewHandle) -> Result<String> {
+    let url = webview.url()?;
+    webview.reload()?;
+    Ok(url)
+}
+
+/// Loads the current page again with `Cache-Control: no-cache` and returns
+/// its URL.
+///
+/// No backend offers a hard reload, so the document itself is revalidated
+/// with the server; subresources it references may still come from the cache.
+#[api]
+fn reload_ignoring_cache() -> Result<String> {
+    let webview = app.app_context()?.get_webview()?;
+    let url = webview.url()?;
+    let mut headers = wry::http::HeaderMap::new();
+    headers.insert(
+        wry::http::header::CACHE_CONTROL,
+        wry::http::HeaderValue::from_static("no-cache"),
+    );
+    headers.insert(
+        wry::http::header::PRAGMA,
+        wry::http::HeaderValue::from_static("no-cache"),
+    );
+    webview.load_url_with_headers(&url, headers)?;
+    Ok(url)
+}
+
+/// Evaluates `script` in the page and defers the answer until its callback
+/// fires; `answer` turns the JSON the script returned into the response.
+fn evaluate_deferred(
+    app: &crate::core::App,
+    req: &ApiRequest,
+    script: &str,
+    answer: fn(&ApiRequest, String) -> ApiResponse,
+) -> Result<()> {
     let webview = app.app_context()?.get_webview()?;
-    webview.close_devtools();
+    let Some(responder) = app.responder(req.0) else {
+        return Ok(());
+    };
+    let responder = std::sync::Arc::new(std::sync::Mutex::new(Some(responder)));
+    let take = |responder: &std::sync::Mutex<Option<_>>| responder.lock().ok()?.take();
+
+    let label = app.label().to_string();
+    let result = webview.evaluate_script_with_callback(script, {
+        let responder = responder.clone();
+        let req = req.clone();
+        let label = label.clone();
+        move |result| {
+            if let Some(responder) = take(&responder) {
+                let _ = responder.send(answer(&req, result).with_label(&label));
+            }
+        }
+    });
+    if let (Err(e), Some(responder)) = (result, take(&responder)) {
+        let _ = responder.send(
+            req.err(ErrorKind::Handler.code(), e.to_string())
+                .with_label(&label),
+        );
+    }
+    Err(Deferred.into())
+}
+
+/// Fails with `503` when the page was still loading, where a scroll position
+/// means nothing yet.
+fn scroll_answer(req: &ApiRequest, result: String) -> ApiResponse {
+    match serde_json::from_str::<serde_json::Value>(&result) {
+        Ok(serde_json::Value::Null) | Err(_) => {
+            req.fail(ApiError::new(ErrorKind::Unavailable, "page not loaded"))
+        }
+        Ok(value) => req.ok(value),
+    }
+}
+
+/// Returns the scroll offset of the page as `{x, y}` in CSS pixels, e.g. to
+/// restore it after a reload; `503` while the page is still loading.
+#[api]
+fn get_scroll_position() -> Result<()> {
+    evaluate_deferred(
+        &app,
+        &req,
+        "document.readyState === \"loading\" ? null : { x: window.scrollX, y: window.scrollY }",
+        scroll_answer,
+    )
+}
+
+/// Scrolls the page to `{x, y}` in CSS pixels and answers with the offset
+/// it ended up at, which is clamped to the document; `503` while the page is
+/// still loading.
+#[api]
+fn set_scroll_position(x: f64, y: f64) -> Result<()> {
+    if !(x.is_finite() && y.is_finite()) {
+        return Err(ApiError::invalid_args("scroll position must be finite").into());
+    }
+    let script = format!(
+        "document.readyState === \"loading\" ? null : (window.scrollTo({}, {}), {{ x: window.scrollX, y: window.scrollY }})",
+        x, y
+    );
+    evaluate_deferred(&app, &req, &script, scroll_answer)
+}
+
+/// No webview backend can change the user agent after creation; it has to
+/// be set through `WebViewOptions.userAgent`.
+#[api]
+fn set_user_agent(_user_agent: String) -> Result<()> {
+    Err(ApiError::new(
+        ErrorKind::NotSupported,
+        "the user agent cannot be changed after the webview was created; set WebViewOptions.userAgent instead",
+    )
+    .into())
+}
+
+/// Validates `proxy` and reports that it cannot be applied.
+///
+/// No webview backend can switch proxies after creation; the proxy has to be
+/// set through `WebViewOptions.proxyConfig`. Malformed proxies get `422`
+/// first so callers learn about both problems.
+#[api]
+fn set_proxy(proxy: String) -> Result<()> {
+    proxy
+        .parse::<pyorion_options::window::ProxyConfig>()
+        .map_err(ApiError::invalid_args)?;
+    Err(ApiError::new(
+        ErrorKind::NotSupported,
+        "the proxy cannot be changed after the webview was created; set WebViewOptions.proxyConfig instead",
+    )
+    .into())
+}
+
+/// Replaces the URL prefixes the webview may navigate to; `null` allows
+/// every navigation again.
+#[api]
+fn set_navigation_allowlist(prefixes: Option<Vec<String>>) -> Result<()> {
+    crate::window::navigation::set_allowlist(prefixes);
     Ok(())
 }
+
+/// Answers a `navigationRequested` event: `allow` loads the URL again and
+/// lets it through, otherwise it is reported as `navigationBlocked`.
+/// Returns whether navigation `id` was still held.
+#[api]
+fn resolve_navigation(id: u64, allow: bool) -> Result<bool> {
+    let Some(url) = crate::window::navigation::resolve(id, allow) else {
+        return Ok(false);
+    };
+    if allow {
+        app.navigate(&url);
+    } else {
+        app.emit("navigationBlocked", serde_json::json!({ "url": url }));
+    }
+    Ok(true)
+}
+
+/// Pushes `message` to the page without a round trip.
+///
+/// The page receives it by defining a callback before messages arrive:
+///
+/// ```js
+/// window.__PYORION_EVENT__ = (message) => { /* any JSON value */ };
+/// ```
+///
+/// Messages sent while no callback is defined are dropped. The value is
+/// embedded as a JSON literal, so it cannot inject script.
+#[api]
+fn post_message(message: serde_json::Value) -> Result<()> {
+    let webview = app.app_context()?.get_webview()?;
+    webview.evaluate_script(&format!(
+        "typeof window.__PYORION_EVENT__ === \"function\" && window.__PYORION_EVENT__({});",
+        crate::utils::js_literal(&message)
+    ))?;
+    Ok(())
+}
+
+/// Returns the webview settings as they took effect at build time, with
+/// defaults filled in and flags the platform ignores reported as `false`.
+#[api]
+fn get_config() -> Result<crate::window::builder::WebViewConfig> {
+    app.app_context()?.get_webview_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::window::builder::WebViewConfig;
+    use std::cell::Cell;
+
+    /// Records what the handlers asked for instead of driving a webview.
+    #[derive(Default)]
+    struct FakeWebView {
+        devtools_open: Cell<bool>,
+        reloads: Cell<u32>,
+    }
+
+    impl WebViewHandle for FakeWebView {
+        fn is_devtools_open(&self) -> bool {
+            self.devtools_open.get()
+        }
+        fn open_devtools(&self) {
+            self.devtools_open.set(true);
+        }
+        fn close_devtools(&self) {
+            self.devtools_open.set(false);
+        }
+        fn set_visible(&self, _visible: bool) -> wry::Result<()> {
+            Ok(())
+        }
+        fn focus(&self) -> wry::Result<()> {
+            Ok(())
+        }
+        fn url(&self) -> wry::Result<String> {
+            Ok("app://localhost/index.html".to_string())
+        }
+        fn reload(&self) -> wry::Result<()> {
+            self.reloads.set(self.reloads.get() + 1);
+            Ok(())
+        }
+    }
+
+    fn config(devtools: bool) -> WebViewConfig {
+        WebViewConfig {
+            label: "main".to_string(),
+            devtools,
+            autoplay: false,
+            clipboard: false,
+            incognito: false,
+            transparent: false,
+            visible: true,
+            focused: true,
+            accept_first_mouse: false,
+            hotkeys_zoom: false,
+            file_drop: true,
+            user_agent: None,
+            data_directory: None,
+        }
+    }
+
+    #[test]
+    fn reload_answers_with_the_current_url() {
+        let webview = FakeWebView::default();
+        assert_eq!(reload_page(&webview).unwrap(), "app://localhost/index.html");
+        assert_eq!(webview.reloads.get(), 1);
+    }
+
+    #[test]
+    fn devtools_toggle_when_enabled() {
+        let webview = FakeWebView::default();
+        set_devtools_open(&webview, &config(true), true).unwrap();
+        assert!(devtools_open(&webview, &config(true)).unwrap());
+        set_devtools_open(&webview, &config(true), false).unwrap();
+        assert!(!webview.devtools_open.get());
+    }
+
+    #[test]
+    fn devtools_disabled_is_forbidden() {
+        let webview = FakeWebView::default();
+        let err = set_devtools_open(&webview, &config(false), true).unwrap_err();
+        let err = err.downcast::<ApiError>().unwrap();
+        assert_eq!(err.code, 403);
+        assert_eq!(err.message, "devtools disabled");
+        assert!(!webview.devtools_open.get());
+
+        let err = devtools_open(&webview, &config(false)).unwrap_err();
+        assert_eq!(
+            err.downcast::<ApiError>().unwrap().kind,
+            ErrorKind::Forbidden
+        );
+    }
+}
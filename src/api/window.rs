@@ -32,16 +32,40 @@ fn set_window_effects(effects: WindowEffectsConfig) -> Result<bool> {
     }
 }
 
+/// Shows or hides the window.
+///
+/// Wrapper for [`tao::window::Window::set_visible`]. With `focus` set, a
+/// window that is being shown (e.g. after a hidden splash setup) also grabs
+/// focus. With `focusWebviewOnShow` the webview then takes keyboard focus.
+///
+/// ## Platform-specific
+/// - Android: Unsupported → returns `false`.
 #[api]
-fn set_visible(visible: bool) -> Result<bool> {
+fn set_visible(visible: bool, focus: Option<bool>) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
         window.set_visible(visible);
+        if visible && focus.unwrap_or(false) {
+            window.set_focus();
+        }
+        if visible {
+            app.window_shown()?;
+        }
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
+/// Answers a `closeRequested` event: `allow` closes the window, otherwise
+/// the close is canceled.
+///
+/// Only meaningful with `closeConfirmTimeoutMs` set; returns `false` when
+/// no close is pending (e.g. it already timed out).
+#[api]
+fn resolve_close(allow: bool) -> Result<bool> {
+    app.resolve_close(allow)
+}
+
 /// Sets the title of the window.
 ///
 /// Wrapper for [`tao::window::Window::set_title`].
@@ -120,9 +144,13 @@ fn set_always_on_top(always_on_top: bool) -> Result<bool> {
 ///
 /// Wrapper for [`tao::window::Window::set_background_color`].
 ///
+/// Takes an `(r, g, b, a)` tuple; `null` restores the system default. This
+/// is the color visible behind the webview, e.g. while resizing.
+///
 /// ## Platform-specific
 /// - Windows: Alpha ignored.
-/// - iOS / Android: Unsupported → returns `false`.
+/// - iOS / Android: Unsupported → `501` not supported error.
+#[cfg(not(any(target_os = "ios", target_os = "android")))]
 #[api]
 fn set_background_color(color: Option<wry::RGBA>) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
@@ -133,9 +161,30 @@ fn set_background_color(color: Option<wry::RGBA>) -> Result<bool> {
     }
 }
 
+#[cfg(any(target_os = "ios", target_os = "android"))]
+#[api]
+fn set_background_color(_color: Option<wry::RGBA>) -> Result<bool> {
+    Err(crate::api_manager::ApiError::not_supported("window background color").into())
+}
+
+/// Emits `windowButtonsChanged` with the state the window reports for its
+/// title bar buttons, which may differ from what was requested on platforms
+/// that ignore a flag.
+fn emit_buttons(app: &crate::core::App, window: &tao::window::Window) {
+    app.emit(
+        "windowButtonsChanged",
+        serde_json::json!({
+            "minimizable": window.is_minimizable(),
+            "maximizable": window.is_maximizable(),
+            "closable": window.is_closable(),
+        }),
+    );
+}
+
 /// Sets whether the window is closable.
 ///
-/// Wrapper for [`tao::window::Window::set_closable`].
+/// Wrapper for [`tao::window::Window::set_closable`]; emits
+/// `windowButtonsChanged`.
 ///
 /// ## Platform-specific
 /// - Linux: May not affect visible windows.
@@ -144,6 +193,7 @@ fn set_background_color(color: Option<wry::RGBA>) -> Result<bool> {
 fn set_closable(closable: bool) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
         window.set_closable(closable);
+        emit_buttons(&app, &window);
         Ok(true)
     } else {
         Ok(false)
@@ -155,7 +205,9 @@ fn set_closable(closable: bool) -> Result<bool> {
 /// Wrapper for [`tao::window::Window::set_content_protection`].
 ///
 /// ## Platform-specific
-/// - iOS / Android / Linux: Unsupported → returns `false`.
+/// - Windows / macOS: Supported.
+/// - iOS / Android / Linux: Unsupported → `501` not supported error.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
 #[api]
 fn set_content_protection(enabled: bool) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
@@ -166,6 +218,12 @@ fn set_content_protection(enabled: bool) -> Result<bool> {
     }
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[api]
+fn set_content_protection(_enabled: bool) -> Result<bool> {
+    Err(crate::api_manager::ApiError::not_supported("content protection").into())
+}
+
 /// Grabs the cursor inside the window.
 ///
 /// Wrapper for [`tao::window::Window::set_cursor_grab`].
@@ -198,18 +256,28 @@ fn set_cursor_icon(cursor: pyorion_options::window::CursorIcon) -> Result<bool>
     }
 }
 
-/// Sets the cursor position in window coordinates.
+/// Moves the cursor to `position`, e.g. to warp it back while dragging on
+/// an infinite canvas.
+///
+/// The position is relative to the top-left corner of the window's client
+/// area, not to the monitor or the desktop. Where the platform refuses to
+/// move the cursor the error is passed on instead of being swallowed.
 ///
 /// Wrapper for [`tao::window::Window::set_cursor_position`].
 ///
 /// ## Platform-specific
-/// - iOS / Android: Unsupported → returns `false`.
+/// - Linux: The move is queued to GTK and always reported as done; Wayland
+///   compositors ignore it.
+/// - iOS / Android: Unsupported → `501`.
 #[api]
 fn set_cursor_position(position: pyorion_options::window::Position) -> Result<bool> {
-    if let Ok(window) = app.app_context()?.get_window() {
-        Ok(window.set_cursor_position(position).is_ok())
-    } else {
-        Ok(false)
+    let window = app.app_context()?.get_window()?;
+    match window.set_cursor_position(position) {
+        Ok(()) => Ok(true),
+        Err(tao::error::ExternalError::NotSupported(_)) => {
+            Err(crate::api_manager::ApiError::not_supported("moving the cursor").into())
+        }
+        Err(err) => Err(err.into()),
     }
 }
 
@@ -280,6 +348,21 @@ fn set_focusable(focusable: bool) -> Result<bool> {
     }
 }
 
+/// Returns whether the window currently has keyboard focus.
+///
+/// Wrapper for [`tao::window::Window::is_focused`].
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns `false`.
+#[api]
+fn is_focused() -> Result<bool> {
+    if let Ok(window) = app.app_context()?.get_window() {
+        Ok(window.is_focused())
+    } else {
+        Ok(false)
+    }
+}
+
 /// Returns list of available monitors.
 ///
 /// Wrapper for [`tao::window::Window::available_monitors`].
@@ -302,22 +385,281 @@ fn get_available_monitors() -> Result<Vec<pyorion_options::window::Monitor>> {
                 x: m.position().x,
                 y: m.position().y,
             },
-            video_modes: m
-                .video_modes()
-                .map(|v| pyorion_options::window::MonitorVideoMode {
-                    size: pyorion_options::window::Dimensions {
-                        width: v.size().width,
-                        height: v.size().height,
-                    },
-                    bit_depth: v.bit_depth(),
-                    refresh_rate: v.refresh_rate(),
-                })
-                .collect(),
+            video_modes: m.video_modes().map(|v| video_mode(&v)).collect(),
         })
         .collect();
     Ok(d)
 }
 
+fn video_mode(v: &tao::monitor::VideoMode) -> pyorion_options::window::MonitorVideoMode {
+    pyorion_options::window::MonitorVideoMode {
+        size: pyorion_options::window::Dimensions {
+            width: v.size().width,
+            height: v.size().height,
+        },
+        bit_depth: v.bit_depth(),
+        refresh_rate: v.refresh_rate(),
+    }
+}
+
+/// Returns the video mode of monitor `name` closest to `query`.
+///
+/// Modes are ranked by distance to the requested size, then to the requested
+/// refresh rate; ties go to the larger size, the higher refresh rate and the
+/// higher bit depth. Without a query this is the monitor's best mode. A
+/// missing monitor or a monitor without modes (common on laptops) is `404`.
+#[api]
+fn best_video_mode(
+    name: String,
+    query: Option<pyorion_options::window::VideoModeQuery>,
+) -> Result<pyorion_options::window::MonitorVideoMode> {
+    use std::cmp::Reverse;
+
+    let query = query.unwrap_or_default();
+    let not_found = |message: String| {
+        crate::api_manager::ApiError::new(crate::api_manager::ErrorKind::NotFound, message)
+    };
+    let window = app.app_context()?.get_window()?;
+    let monitor = window
+        .available_monitors()
+        .find(|monitor| monitor.name().as_deref() == Some(name.as_str()))
+        .ok_or_else(|| not_found(format!("monitor not found: {}", name)))?;
+
+    let distance = |want: Option<u32>, have: u32| want.map_or(0, |want| want.abs_diff(have));
+    let best = monitor
+        .video_modes()
+        .min_by_key(|v| {
+            let size = v.size();
+            (
+                distance(query.width, size.width) as u64
+                    + distance(query.height, size.height) as u64,
+                distance(query.refresh_rate.map(u32::from), v.refresh_rate().into()),
+                Reverse(size.width as u64 * size.height as u64),
+                Reverse(v.refresh_rate()),
+                Reverse(v.bit_depth()),
+            )
+        })
+        .ok_or_else(|| not_found(format!("monitor {} has no video modes", name)))?;
+    Ok(video_mode(&best))
+}
+
+/// Work area of `monitor` as reported by `GetMonitorInfoW`.
+#[cfg(target_os = "windows")]
+fn platform_work_area(
+    monitor: &tao::monitor::MonitorHandle,
+) -> Option<pyorion_options::window::WorkArea> {
+    use tao::platform::windows::MonitorHandleExtWindows;
+    use windows::Win32::Graphics::Gdi::{GetMonitorInfoW, HMONITOR, MONITORINFO};
+
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    let ok = unsafe { GetMonitorInfoW(HMONITOR(monitor.hmonitor() as _), &mut info) };
+    if !ok.as_bool() {
+        return None;
+    }
+    let rect = info.rcWork;
+    Some(pyorion_options::window::WorkArea {
+        position: pyorion_options::window::MonitorPosition {
+            x: rect.left,
+            y: rect.top,
+        },
+        size: pyorion_options::window::Dimensions {
+            width: (rect.right - rect.left).max(0) as u32,
+            height: (rect.bottom - rect.top).max(0) as u32,
+        },
+        fallback: false,
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_work_area(
+    _monitor: &tao::monitor::MonitorHandle,
+) -> Option<pyorion_options::window::WorkArea> {
+    None
+}
+
+/// Returns the usable area of monitor `name`, or of the window's current
+/// monitor when omitted, excluding the taskbar or dock.
+///
+/// Where the platform does not report a work area, the full monitor bounds
+/// are returned with `fallback` set. A missing monitor is `404`.
+///
+/// ## Platform-specific
+/// - macOS / Linux: Always the full monitor bounds.
+#[api]
+fn work_area(name: Option<String>) -> Result<pyorion_options::window::WorkArea> {
+    let window = app.app_context()?.get_window()?;
+    let monitor = match &name {
+        Some(name) => window
+            .available_monitors()
+            .find(|monitor| monitor.name().as_deref() == Some(name.as_str())),
+        None => window
+            .current_monitor()
+            .or_else(|| window.primary_monitor()),
+    }
+    .ok_or_else(|| {
+        crate::api_manager::ApiError::new(
+            crate::api_manager::ErrorKind::NotFound,
+            match &name {
+                Some(name) => format!("monitor not found: {}", name),
+                None => "no monitor found".to_string(),
+            },
+        )
+    })?;
+    Ok(
+        platform_work_area(&monitor).unwrap_or_else(|| pyorion_options::window::WorkArea {
+            position: monitor.position().into(),
+            size: monitor.size().into(),
+            fallback: true,
+        }),
+    )
+}
+
+/// Whether the global point `(x, y)` lies on `monitor`.
+fn contains(monitor: &tao::monitor::MonitorHandle, x: i32, y: i32) -> bool {
+    let (mp, ms) = (monitor.position(), monitor.size());
+    let (x, y) = (x as i64, y as i64);
+    (mp.x as i64..mp.x as i64 + ms.width as i64).contains(&x)
+        && (mp.y as i64..mp.y as i64 + ms.height as i64).contains(&y)
+}
+
+/// Converts a point in global desktop coordinates to one relative to the
+/// monitor it lies on.
+///
+/// Global coordinates span all monitors and may be negative left of or
+/// above the primary monitor. A point on no monitor is `404`.
+#[api]
+fn global_to_local(x: i32, y: i32) -> Result<pyorion_options::window::LocalPosition> {
+    let window = app.app_context()?.get_window()?;
+    let monitor = window
+        .available_monitors()
+        .find(|monitor| contains(monitor, x, y))
+        .ok_or_else(|| {
+            crate::api_manager::ApiError::new(
+                crate::api_manager::ErrorKind::NotFound,
+                format!("no monitor at {}, {}", x, y),
+            )
+        })?;
+    let origin = monitor.position();
+    Ok(pyorion_options::window::LocalPosition {
+        monitor: monitor.name(),
+        x: x - origin.x,
+        y: y - origin.y,
+    })
+}
+
+/// Converts a point relative to the top-left corner of monitor `name`, or of
+/// the primary monitor when omitted, to global desktop coordinates.
+///
+/// The point may lie outside the monitor; a missing monitor is `404`.
+#[api]
+fn local_to_global(
+    x: i32,
+    y: i32,
+    name: Option<String>,
+) -> Result<pyorion_options::window::MonitorPosition> {
+    let window = app.app_context()?.get_window()?;
+    let monitor = match &name {
+        Some(name) => window
+            .available_monitors()
+            .find(|monitor| monitor.name().as_deref() == Some(name.as_str())),
+        None => window
+            .primary_monitor()
+            .or_else(|| window.available_monitors().next()),
+    }
+    .ok_or_else(|| {
+        crate::api_manager::ApiError::new(
+            crate::api_manager::ErrorKind::NotFound,
+            match &name {
+                Some(name) => format!("monitor not found: {}", name),
+                None => "no monitor found".to_string(),
+            },
+        )
+    })?;
+    let origin = monitor.position();
+    Ok(pyorion_options::window::MonitorPosition {
+        x: origin.x.saturating_add(x),
+        y: origin.y.saturating_add(y),
+    })
+}
+
+/// Area shared by the window rectangle and `monitor`, in physical pixels.
+fn overlap(window: &tao::window::Window, monitor: &tao::monitor::MonitorHandle) -> i64 {
+    let (wp, ws) = match window.outer_position() {
+        Ok(pos) => (pos, window.outer_size()),
+        Err(_) => return 0,
+    };
+    let (mp, ms) = (monitor.position(), monitor.size());
+    let w = (wp.x + ws.width as i32).min(mp.x + ms.width as i32) - wp.x.max(mp.x);
+    let h = (wp.y + ws.height as i32).min(mp.y + ms.height as i32) - wp.y.max(mp.y);
+    w.max(0) as i64 * h.max(0) as i64
+}
+
+/// Moves `window` so that it is centered on `monitor`.
+///
+/// A window larger than the monitor is pinned to the monitor's top-left corner.
+fn center_on_monitor(window: &tao::window::Window, monitor: &tao::monitor::MonitorHandle) {
+    let (mp, ms, ws) = (monitor.position(), monitor.size(), window.outer_size());
+    let x = mp.x + (ms.width.saturating_sub(ws.width) / 2) as i32;
+    let y = mp.y + (ms.height.saturating_sub(ws.height) / 2) as i32;
+    window.set_outer_position(tao::dpi::PhysicalPosition::new(x, y));
+}
+
+/// Centers the window on the monitor it is currently on.
+///
+/// When the window spans several monitors, the one covering the largest part
+/// of it wins.
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns `false`.
+#[api]
+fn center() -> Result<bool> {
+    if let Ok(window) = app.app_context()?.get_window() {
+        let monitor = window
+            .available_monitors()
+            .max_by_key(|monitor| overlap(&window, monitor))
+            .filter(|monitor| overlap(&window, monitor) > 0)
+            .or_else(|| window.current_monitor())
+            .or_else(|| window.primary_monitor());
+        match monitor {
+            Some(monitor) => {
+                center_on_monitor(&window, &monitor);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    } else {
+        Ok(false)
+    }
+}
+
+/// Centers the window on the monitor called `name`.
+///
+/// Fails with `404` if no such monitor is connected (anymore).
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns `false`.
+#[api]
+fn center_on(name: String) -> Result<bool> {
+    if let Ok(window) = app.app_context()?.get_window() {
+        let monitor = window
+            .available_monitors()
+            .find(|monitor| monitor.name().as_deref() == Some(name.as_str()))
+            .ok_or_else(|| {
+                crate::api_manager::ApiError::new(
+                    crate::api_manager::ErrorKind::NotFound,
+                    format!("monitor not found: {}", name),
+                )
+            })?;
+        center_on_monitor(&window, &monitor);
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
 /// Toggles fullscreen.
 ///
 /// Wrapper for [`tao::window::Window::set_fullscreen`].
@@ -344,14 +686,23 @@ fn set_fullscreen(fullscreen: bool) -> Result<bool> {
 
 /// Ignores or catches cursor events.
 ///
+/// With `true` clicks pass through to whatever is behind the window, which
+/// together with `transparent` and `alwaysOnTop` makes click-through overlays.
+///
 /// Wrapper for [`tao::window::Window::set_ignore_cursor_events`].
 ///
 /// ## Platform-specific
-/// - iOS / Android: Unsupported → returns `false`.
+/// - iOS / Android: Unsupported → `501` not supported error.
 #[api]
 fn set_ignore_cursor_events(ignore: bool) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
-        Ok(window.set_ignore_cursor_events(ignore).is_ok())
+        match window.set_ignore_cursor_events(ignore) {
+            Ok(()) => Ok(true),
+            Err(tao::error::ExternalError::NotSupported(_)) => {
+                Err(crate::api_manager::ApiError::not_supported("ignoring cursor events").into())
+            }
+            Err(e) => Err(anyhow::anyhow!("Failed to set ignore cursor events: {}", e)),
+        }
     } else {
         Ok(false)
     }
@@ -359,10 +710,18 @@ fn set_ignore_cursor_events(ignore: bool) -> Result<bool> {
 
 /// Sets IME candidate box position.
 ///
+/// The position is in client area coordinates relative to the top left;
+/// logical and physical positions are both accepted. Also registered as
+/// `window.setImeCursorArea`.
+///
 /// Wrapper for [`tao::window::Window::set_ime_position`].
 ///
 /// ## Platform-specific
-/// - iOS / Android: Unsupported → returns `false`.
+/// - Windows / macOS: Supported.
+/// - Linux: The GTK backend ignores the call; the webview places the
+///   candidate box for its own inputs → `501` not supported error.
+/// - iOS / Android: Unsupported → `501` not supported error.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
 #[api]
 fn set_ime_position(position: pyorion_options::window::Position) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
@@ -373,6 +732,22 @@ fn set_ime_position(position: pyorion_options::window::Position) -> Result<bool>
     }
 }
 
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+#[api]
+fn set_ime_position(_position: pyorion_options::window::Position) -> Result<bool> {
+    Err(crate::api_manager::ApiError::not_supported("setting the IME position").into())
+}
+
+/// Enables or disables IME input for the window.
+///
+/// tao offers no switch for this on any platform; IME inside the webview is
+/// controlled by the focused element (e.g. `inputmode="none"`) instead.
+/// Always a `501` not supported error.
+#[api]
+fn set_ime_allowed(_allowed: bool) -> Result<bool> {
+    Err(crate::api_manager::ApiError::not_supported("toggling IME input").into())
+}
+
 /// Sets progress bar state.
 ///
 /// Wrapper for [`tao::window::Window::set_progress_bar`].
@@ -399,7 +774,7 @@ fn set_progress_bar(progress: pyorion_options::window::ProgressBarState) -> Resu
 #[api]
 fn set_inner_size(size: pyorion_options::window::Size) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
-        window.set_inner_size(size);
+        window.set_inner_size::<tao::dpi::Size>(size.into());
         Ok(true)
     } else {
         Ok(false)
@@ -417,23 +792,99 @@ fn set_inner_size_constraints(
     constraints: pyorion_options::window::WindowSizeConstraints,
 ) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
-        window.set_inner_size_constraints(constraints.into());
+        let constraints: tao::window::WindowSizeConstraints = constraints.into();
+        window.set_inner_size_constraints(constraints);
+        app.update_size_constraints(|current| *current = constraints)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Sets the inner size constraints after validating them.
+///
+/// Wrapper for [`tao::window::Window::set_inner_size_constraints`]. Fails with
+/// `422` if a bound is negative or a minimum is larger than the
+/// corresponding maximum.
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns `false`.
+#[api]
+fn set_size_constraints(
+    constraints: pyorion_options::window::WindowSizeConstraints,
+) -> Result<bool> {
+    constraints
+        .validate()
+        .map_err(crate::api_manager::ApiError::invalid_args)?;
+    if let Ok(window) = app.app_context()?.get_window() {
+        let constraints: tao::window::WindowSizeConstraints = constraints.into();
+        window.set_inner_size_constraints(constraints);
+        app.update_size_constraints(|current| *current = constraints)?;
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
+/// Returns the inner size the window would get for `size` under its current
+/// size constraints, in physical pixels, without resizing it.
+#[api]
+fn will_resize_to(
+    size: pyorion_options::window::Size,
+) -> Result<pyorion_options::window::Dimensions> {
+    let window = app.app_context()?.get_window()?;
+    let scale_factor = window.scale_factor();
+    let clamped = app
+        .size_constraints()?
+        .clamp(size.into(), scale_factor)
+        .to_physical::<u32>(scale_factor);
+    Ok(clamped.into())
+}
+
+/// Fails with `422` when a minimum of `constraints` exceeds its maximum,
+/// comparing both in physical pixels since either may be logical.
+fn check_size_constraints(
+    constraints: &tao::window::WindowSizeConstraints,
+    scale_factor: f64,
+) -> Result<()> {
+    let axes = [
+        ("width", constraints.min_width, constraints.max_width),
+        ("height", constraints.min_height, constraints.max_height),
+    ];
+    for (axis, min, max) in axes {
+        let (Some(min), Some(max)) = (min, max) else {
+            continue;
+        };
+        let min = min.to_physical::<f64>(scale_factor).0;
+        let max = max.to_physical::<f64>(scale_factor).0;
+        if min > max {
+            return Err(crate::api_manager::ApiError::invalid_args(format!(
+                "minimum {} ({}) is larger than maximum {} ({})",
+                axis, min, axis, max
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
 /// Sets max inner size.
 ///
-/// Wrapper for [`tao::window::Window::set_max_inner_size`].
+/// Wrapper for [`tao::window::Window::set_max_inner_size`]. Fails with `422`
+/// if the maximum is below the current minimum.
 ///
 /// ## Platform-specific
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_max_inner_size(max_size: pyorion_options::window::Size) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
+        let (width, height) = max_size.pixel_units();
+        let mut constraints = app.size_constraints()?;
+        constraints.max_width = Some(width);
+        constraints.max_height = Some(height);
+        check_size_constraints(&constraints, window.scale_factor())?;
         window.set_max_inner_size(Some(max_size));
+        app.update_size_constraints(|current| *current = constraints)?;
         Ok(true)
     } else {
         Ok(false)
@@ -442,7 +893,8 @@ fn set_max_inner_size(max_size: pyorion_options::window::Size) -> Result<bool> {
 
 /// Sets maximizable flag.
 ///
-/// Wrapper for [`tao::window::Window::set_maximizable`].
+/// Wrapper for [`tao::window::Window::set_maximizable`]; emits
+/// `windowButtonsChanged`.
 ///
 /// ## Platform-specific
 /// - macOS: Disables zoom button.
@@ -451,6 +903,7 @@ fn set_max_inner_size(max_size: pyorion_options::window::Size) -> Result<bool> {
 fn set_maximizable(maximizable: bool) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
         window.set_maximizable(maximizable);
+        emit_buttons(&app, &window);
         Ok(true)
     } else {
         Ok(false)
@@ -475,14 +928,21 @@ fn set_minimized(minimized: bool) -> Result<bool> {
 
 /// Sets minimum inner size.
 ///
-/// Wrapper for [`tao::window::Window::set_min_inner_size`].
+/// Wrapper for [`tao::window::Window::set_min_inner_size`]. Fails with `422`
+/// if the minimum is above the current maximum.
 ///
 /// ## Platform-specific
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
 fn set_min_inner_size(min_size: pyorion_options::window::Size) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
+        let (width, height) = min_size.pixel_units();
+        let mut constraints = app.size_constraints()?;
+        constraints.min_width = Some(width);
+        constraints.min_height = Some(height);
+        check_size_constraints(&constraints, window.scale_factor())?;
         window.set_min_inner_size(Some(min_size));
+        app.update_size_constraints(|current| *current = constraints)?;
         Ok(true)
     } else {
         Ok(false)
@@ -491,7 +951,8 @@ fn set_min_inner_size(min_size: pyorion_options::window::Size) -> Result<bool> {
 
 /// Sets minimizable flag.
 ///
-/// Wrapper for [`tao::window::Window::set_minimizable`].
+/// Wrapper for [`tao::window::Window::set_minimizable`]; emits
+/// `windowButtonsChanged`.
 ///
 /// ## Platform-specific
 /// - Linux / iOS / Android: Unsupported → returns `false`.
@@ -499,6 +960,7 @@ fn set_min_inner_size(min_size: pyorion_options::window::Size) -> Result<bool> {
 fn set_minimizable(minimizable: bool) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
         window.set_minimizable(minimizable);
+        emit_buttons(&app, &window);
         Ok(true)
     } else {
         Ok(false)
@@ -521,32 +983,90 @@ fn set_outer_position(position: pyorion_options::window::Position) -> Result<boo
     }
 }
 
+/// Moves the window to the given position.
+///
+/// Wrapper for [`tao::window::Window::set_outer_position`]; the unit of the
+/// position (logical / physical) is taken from `position.unit`.
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns `false`.
+#[api]
+fn set_position(position: pyorion_options::window::Position) -> Result<bool> {
+    if let Ok(window) = app.app_context()?.get_window() {
+        window.set_outer_position::<tao::dpi::Position>(position.into());
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Returns the physical position of the top-left corner of the client area.
+///
+/// Wrapper for [`tao::window::Window::inner_position`].
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns an error.
+#[api]
+fn get_inner_position() -> Result<pyorion_options::window::MonitorPosition> {
+    let window = app.app_context()?.get_window()?;
+    let position = window
+        .inner_position()
+        .map_err(|e| anyhow::anyhow!("Inner position not available: {}", e))?;
+    Ok(position.into())
+}
+
+/// Returns the physical position of the top-left corner of the window.
+///
+/// Wrapper for [`tao::window::Window::outer_position`].
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns an error.
+#[api]
+fn get_outer_position() -> Result<pyorion_options::window::MonitorPosition> {
+    let window = app.app_context()?.get_window()?;
+    let position = window
+        .outer_position()
+        .map_err(|e| anyhow::anyhow!("Outer position not available: {}", e))?;
+    Ok(position.into())
+}
+
 /// Sets window theme.
 ///
-/// Wrapper for [`tao::window::Window::set_theme`].
+/// Wrapper for [`tao::window::Window::set_theme`]. Passing `null` makes the
+/// window follow the system theme again.
 ///
 /// ## Platform-specific
 /// - iOS / Android: Unsupported → returns `false`.
 #[api]
-fn set_theme(theme: pyorion_options::window::Theme) -> Result<bool> {
+fn set_theme(theme: Option<pyorion_options::window::Theme>) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
-        let main_theme = match theme {
-            pyorion_options::window::Theme::Light => tao::window::Theme::Light,
-            pyorion_options::window::Theme::Dark => tao::window::Theme::Dark,
-        };
-        window.set_theme(Some(main_theme));
+        window.set_theme(theme.map(Into::into));
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
+/// Returns the current window theme.
+///
+/// Wrapper for [`tao::window::Window::theme`].
+///
+/// ## Platform-specific
+/// - iOS / Android: Unsupported → returns `light`.
+#[api]
+fn get_theme() -> Result<pyorion_options::window::Theme> {
+    let window = app.app_context()?.get_window()?;
+    Ok(window.theme().into())
+}
+
 /// Sets whether visible on all workspaces.
 ///
 /// Wrapper for [`tao::window::Window::set_visible_on_all_workspaces`].
 ///
 /// ## Platform-specific
-/// - iOS / Android: Unsupported → returns `false`.
+/// - macOS / Linux: Supported.
+/// - iOS / Android / Windows: Unsupported → `501` not supported error.
+#[cfg(not(any(target_os = "windows", target_os = "ios", target_os = "android")))]
 #[api]
 fn set_visible_on_all_workspaces(visible: bool) -> Result<bool> {
     if let Ok(window) = app.app_context()?.get_window() {
@@ -557,6 +1077,12 @@ fn set_visible_on_all_workspaces(visible: bool) -> Result<bool> {
     }
 }
 
+#[cfg(any(target_os = "windows", target_os = "ios", target_os = "android"))]
+#[api]
+fn set_visible_on_all_workspaces(_visible: bool) -> Result<bool> {
+    Err(crate::api_manager::ApiError::not_supported("visible on all workspaces").into())
+}
+
 #[cfg(target_os = "windows")]
 #[api]
 fn set_enable(enable: bool) -> Result<bool> {
@@ -611,6 +1137,119 @@ fn set_undecorated_shadow(_shadow: bool) -> Result<bool> {
     Ok(false)
 }
 
+/// Hides the window from the taskbar and alt-tab, or shows it there again.
+///
+/// ## Platform-specific
+/// - macOS / iOS / Android: Unsupported → `501`.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+#[api]
+fn set_skip_taskbar(skip: bool) -> Result<bool> {
+    #[cfg(target_os = "linux")]
+    use tao::platform::unix::WindowExtUnix;
+    #[cfg(target_os = "windows")]
+    use tao::platform::windows::WindowExtWindows;
+    if let Ok(window) = app.app_context()?.get_window() {
+        window.set_skip_taskbar(skip)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+#[api]
+fn set_skip_taskbar(_skip: bool) -> Result<bool> {
+    Err(crate::api_manager::ApiError::not_supported("skipping the taskbar").into())
+}
+
+/// Applies `opacity` through `SetLayeredWindowAttributes`.
+#[cfg(target_os = "windows")]
+fn platform_set_opacity(window: &tao::window::Window, opacity: f64) -> Result<()> {
+    use tao::platform::windows::WindowExtWindows;
+    use windows::Win32::{
+        Foundation::{COLORREF, HWND},
+        UI::WindowsAndMessaging::{
+            GetWindowLongPtrW, SetLayeredWindowAttributes, SetWindowLongPtrW, GWL_EXSTYLE,
+            LWA_ALPHA, WS_EX_LAYERED,
+        },
+    };
+
+    let hwnd = HWND(window.hwnd() as _);
+    unsafe {
+        let style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, style | WS_EX_LAYERED.0 as isize);
+        SetLayeredWindowAttributes(
+            hwnd,
+            COLORREF(0),
+            (opacity * 255.0).round() as u8,
+            LWA_ALPHA,
+        )?;
+    }
+    Ok(())
+}
+
+/// Applies `opacity` to the GTK window; needs a compositing window manager.
+#[cfg(target_os = "linux")]
+fn platform_set_opacity(window: &tao::window::Window, opacity: f64) -> Result<()> {
+    use gtk::prelude::WidgetExt;
+    use tao::platform::unix::WindowExtUnix;
+    window.gtk_window().set_opacity(opacity);
+    Ok(())
+}
+
+/// Applies `opacity` as the `alphaValue` of the `NSWindow`.
+#[cfg(target_os = "macos")]
+fn platform_set_opacity(window: &tao::window::Window, opacity: f64) -> Result<()> {
+    use tao::platform::macos::WindowExtMacOS;
+    let ns_window = unsafe { &*(window.ns_window() as *const objc2_app_kit::NSWindow) };
+    ns_window.setAlphaValue(opacity);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+fn platform_set_opacity(_window: &tao::window::Window, _opacity: f64) -> Result<()> {
+    Err(crate::api_manager::ApiError::not_supported("window opacity").into())
+}
+
+/// Sets the opacity of the whole window, decorations included, from `0.0`
+/// (invisible) to `1.0` (opaque) and returns the value applied.
+///
+/// Values outside that range are clamped; `NaN` is `422`. Unlike the
+/// `transparent` options this fades the content as a whole, e.g. to fade a
+/// window in or dim it while inactive.
+///
+/// ## Platform-specific
+/// - Linux: Needs a compositing window manager, otherwise has no effect.
+/// - iOS / Android: Unsupported → `501`.
+#[api]
+fn set_opacity(opacity: f64) -> Result<f64> {
+    if opacity.is_nan() {
+        return Err(crate::api_manager::ApiError::invalid_args("opacity must be a number").into());
+    }
+    let opacity = opacity.clamp(0.0, 1.0);
+    let window = app.app_context()?.get_window()?;
+    platform_set_opacity(&window, opacity)?;
+    Ok(opacity)
+}
+
+/// Returns the physical inner size of the window.
+///
+/// Wrapper for [`tao::window::Window::inner_size`].
+#[api]
+fn get_inner_size() -> Result<pyorion_options::window::Dimensions> {
+    let window = app.app_context()?.get_window()?;
+    Ok(window.inner_size().into())
+}
+
+/// Returns the physical outer size of the window, including decorations.
+///
+/// Wrapper for [`tao::window::Window::outer_size`].
+#[api]
+fn get_outer_size() -> Result<pyorion_options::window::Dimensions> {
+    let window = app.app_context()?.get_window()?;
+    Ok(window.outer_size().into())
+}
+
 /// Returns inner size.
 #[api]
 fn inner_size() -> Result<tao::dpi::PhysicalSize<u32>> {
@@ -636,40 +1275,77 @@ pub fn window_api(api_manager: &mut ApiManager) {
     api_manager.register_api("window.set_title", set_title);
     api_manager.register_api("window.get_title", get_title);
     api_manager.register_api("window.scale_factor", scale_factor);
+    api_manager.register_api("window.getScaleFactor", scale_factor);
     api_manager.register_api("window.set_always_on_bottom", set_always_on_bottom);
     api_manager.register_api("window.set_always_on_top", set_always_on_top);
     api_manager.register_api("window.set_background_color", set_background_color);
+    api_manager.register_api("window.setBackgroundColor", set_background_color);
     api_manager.register_api("window.set_closable", set_closable);
     api_manager.register_api("window.set_content_protection", set_content_protection);
+    api_manager.register_api("window.setContentProtection", set_content_protection);
     api_manager.register_api("window.set_cursor_grab", set_cursor_grab);
     api_manager.register_api("window.set_cursor_icon", set_cursor_icon);
     api_manager.register_api("window.set_cursor_position", set_cursor_position);
+    api_manager.register_api("window.setCursorPosition", set_cursor_position);
     api_manager.register_api("window.set_cursor_visible", set_cursor_visible);
     api_manager.register_api("window.set_decorations", set_decorations);
     api_manager.register_api("window.set_focus", set_focus);
     api_manager.register_api("window.set_focusable", set_focusable);
+    api_manager.register_api("window.setFocusable", set_focusable);
+    api_manager.register_api("window.focus", set_focus);
+    api_manager.register_api("window.isFocused", is_focused);
     api_manager.register_api("window.get_available_monitors", get_available_monitors);
     api_manager.register_api("window.set_fullscreen", set_fullscreen);
     api_manager.register_api("window.set_ignore_cursor_events", set_ignore_cursor_events);
+    api_manager.register_api("window.setIgnoreCursorEvents", set_ignore_cursor_events);
     api_manager.register_api("window.set_ime_position", set_ime_position);
+    api_manager.register_api("window.setImePosition", set_ime_position);
+    api_manager.register_api("window.setImeCursorArea", set_ime_position);
+    api_manager.register_api("window.setImeAllowed", set_ime_allowed);
     api_manager.register_api("window.set_progress_bar", set_progress_bar);
     api_manager.register_api("window.set_inner_size", set_inner_size);
+    api_manager.register_api("window.setInnerSize", set_inner_size);
+    api_manager.register_api("window.getInnerSize", get_inner_size);
+    api_manager.register_api("window.getOuterSize", get_outer_size);
     api_manager.register_api(
         "window.set_inner_size_constraints",
         set_inner_size_constraints,
     );
     api_manager.register_api("window.set_max_inner_size", set_max_inner_size);
+    api_manager.register_api("window.setMaxInnerSize", set_max_inner_size);
+    api_manager.register_api("window.setSizeConstraints", set_size_constraints);
+    api_manager.register_api("window.willResizeTo", will_resize_to);
+    api_manager.register_api("window.setSkipTaskbar", set_skip_taskbar);
+    api_manager.register_api("window.setOpacity", set_opacity);
     api_manager.register_api("window.set_maximizable", set_maximizable);
     api_manager.register_api("window.set_minimized", set_minimized);
     api_manager.register_api("window.set_min_inner_size", set_min_inner_size);
+    api_manager.register_api("window.setMinInnerSize", set_min_inner_size);
     api_manager.register_api("window.set_minimizable", set_minimizable);
     api_manager.register_api("window.set_outer_position", set_outer_position);
+    api_manager.register_api("window.setPosition", set_position);
+    api_manager.register_api("window.getInnerPosition", get_inner_position);
+    api_manager.register_api("window.getOuterPosition", get_outer_position);
+    api_manager.register_api("window.center", center);
+    api_manager.register_api("window.centerOn", center_on);
+    api_manager.register_api("monitor.bestVideoMode", best_video_mode);
+    api_manager.register_api("monitor.workArea", work_area);
+    api_manager.register_api("monitor.globalToLocal", global_to_local);
+    api_manager.register_api("monitor.localToGlobal", local_to_global);
     api_manager.register_api("window.set_theme", set_theme);
+    api_manager.register_api("window.setTheme", set_theme);
+    api_manager.register_api("window.getTheme", get_theme);
     api_manager.register_api("window.set_visible", set_visible);
+    api_manager.register_api("window.setVisible", set_visible);
+    api_manager.register_api("window.resolveClose", resolve_close);
     api_manager.register_api(
         "window.set_visible_on_all_workspaces",
         set_visible_on_all_workspaces,
     );
+    api_manager.register_api(
+        "window.setVisibleOnAllWorkspaces",
+        set_visible_on_all_workspaces,
+    );
     api_manager.register_api("window.set_enable", set_enable);
     api_manager.register_api("window.set_rtl", set_rtl);
     api_manager.register_api("window.set_undecorated_shadow", set_undecorated_shadow);
@@ -678,3 +1354,29 @@ pub fn window_api(api_manager: &mut ApiManager) {
     api_manager.register_api("window.outer_position", outer_position);
     api_manager.register_api("window.set_window_effect", set_window_effects);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tao::dpi::{LogicalUnit, PhysicalUnit, PixelUnit};
+
+    #[test]
+    fn size_constraints_compare_in_physical_pixels() {
+        let mut constraints = tao::window::WindowSizeConstraints {
+            min_width: Some(PixelUnit::Logical(LogicalUnit::new(400.0))),
+            max_width: Some(PixelUnit::Physical(PhysicalUnit::new(600))),
+            ..Default::default()
+        };
+        assert!(check_size_constraints(&constraints, 1.0).is_ok());
+
+        // 400 logical pixels are 800 physical ones at 2x
+        let err = check_size_constraints(&constraints, 2.0).unwrap_err();
+        let err = err.downcast::<crate::api_manager::ApiError>().unwrap();
+        assert_eq!(err.code, 422);
+        assert!(err.message.contains("width"));
+
+        constraints.min_height = Some(PixelUnit::Physical(PhysicalUnit::new(300)));
+        constraints.max_height = Some(PixelUnit::Physical(PhysicalUnit::new(200)));
+        assert!(check_size_constraints(&constraints, 1.0).is_err());
+    }
+}
@@ -36,21 +36,304 @@ pub struct ApiRequest(pub u8, pub String, pub ApiArguments);
 impl ApiRequest {
     #[allow(dead_code)]
     pub fn err<C: Into<i32>, S: Into<String>>(&self, code: C, msg: S) -> ApiResponse {
-        ApiResponse(self.0, code.into(), msg.into(), json!(null))
+        let code = code.into();
+        ApiError::with_code(code, ErrorKind::from_code(code), msg).into_response(self.0)
+    }
+    #[allow(dead_code)]
+    pub fn fail(&self, error: ApiError) -> ApiResponse {
+        error.into_response(self.0)
     }
     #[allow(dead_code)]
     pub fn ok<D: Serialize>(&self, data: D) -> ApiResponse {
-        ApiResponse(self.0, 0, "ok".to_string(), json!(data))
+        ApiResponse(self.0, 0, "ok".to_string(), json!(data), None, None)
+    }
+    /// Answers with raw bytes sent after the frame instead of inside it.
+    pub fn binary(&self, bytes: Vec<u8>) -> ApiResponse {
+        ApiResponse(
+            self.0,
+            0,
+            "ok".to_string(),
+            json!(bytes.len()),
+            None,
+            Some(bytes),
+        )
     }
     #[allow(dead_code)]
     pub fn args(&self) -> &ApiArguments {
         &self.2
     }
 }
+/// Where a [`RequestContext`] sends its events: the event loop proxy in the
+/// app, which forwards them to the webview.
+pub trait EventSink: Send {
+    fn send(&self, event: crate::utils::UserEvent);
+}
+
+impl EventSink for crate::utils::FrameEventLoopProxy {
+    fn send(&self, event: crate::utils::UserEvent) {
+        let _ = self.send_event(event);
+    }
+}
+
+/// Where a request came from, available as `ctx` inside `#[api]` handlers.
+///
+/// Unlike `app` it can be moved to another thread, so deferred handlers can
+/// keep reporting from there.
+#[allow(dead_code)]
+pub struct RequestContext {
+    /// Id of the request being handled.
+    pub id: u8,
+    /// Name of the API being called.
+    pub api: String,
+    /// Label of the webview the request targets.
+    pub label: String,
+    /// Native id of the window hosting that webview.
+    pub window_id: tao::window::WindowId,
+    events: Box<dyn EventSink>,
+}
+
+#[allow(dead_code)]
+impl RequestContext {
+    pub fn new(app: &Arc<App>, req: &ApiRequest) -> Self {
+        Self {
+            id: req.0,
+            api: req.1.clone(),
+            label: app.label().to_string(),
+            window_id: app.window_id(),
+            events: Box::new(app.proxy.clone()),
+        }
+    }
+
+    /// Reports how far the call got as a `progress` event:
+    /// `{ requestId, api, percent, message }`, with `percent` capped at 100.
+    ///
+    /// Events travel through the webview, not the request connection, so
+    /// only handlers that answer later (see [`Deferred`]) can rely on their
+    /// progress arriving before the response.
+    pub fn progress(&self, percent: u8, message: Option<&str>) {
+        self.emit(
+            "progress",
+            json!({
+                "api": self.api,
+                "percent": percent.min(100),
+                "message": message,
+            }),
+        );
+    }
+
+    /// Emits `event` with the request id added as `requestId`, so the caller
+    /// can match it to its pending call. Object payloads get the field
+    /// merged in; anything else is wrapped as `{ requestId, payload }`.
+    pub fn emit<S: Into<String>, T: Serialize>(&self, event: S, payload: T) {
+        let payload = match json!(payload) {
+            Value::Object(mut fields) => {
+                fields.insert("requestId".to_string(), json!(self.id));
+                Value::Object(fields)
+            }
+            other => json!({ "requestId": self.id, "payload": other }),
+        };
+        self.events
+            .send(crate::utils::UserEvent::Emit(event.into(), payload));
+    }
+}
+
 pub type Code = i32;
+/// `[id, code, message, data, label]`, where `label` names the webview that
+/// answered (`null` for connection-level answers such as `system.auth`).
+///
+/// The last field holds raw bytes of a [`Binary`] result. It is never part
+/// of the serialized frame: the connection layer sends it as a trailing
+/// binary part, with `data` set to its length, or inlines it as base64.
 #[allow(dead_code)]
 #[derive(Serialize, Clone)]
-pub struct ApiResponse(pub u8, pub Code, pub String, pub Value);
+pub struct ApiResponse(
+    pub u8,
+    pub Code,
+    pub String,
+    pub Value,
+    pub Option<String>,
+    #[serde(skip)] pub Option<Vec<u8>>,
+);
+
+impl ApiResponse {
+    /// Stamps the label of the answering webview unless one is set already.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.4.get_or_insert_with(|| label.to_string());
+        self
+    }
+    /// Moves trailing bytes into `data` as a base64 string, for peers that
+    /// cannot receive a binary part.
+    pub fn inline_binary(mut self) -> Self {
+        if let Some(bytes) = self.5.take() {
+            self.3 = json!(Binary(bytes));
+        }
+        self
+    }
+}
+
+/// Handler result carrying raw bytes.
+///
+/// Sent as a trailing binary part to clients that negotiated it during the
+/// handshake, which avoids the base64 inflation of roughly a third. Other
+/// clients receive a base64 string, so it serializes as one.
+pub struct Binary(pub Vec<u8>);
+
+impl Serialize for Binary {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use base64::Engine as _;
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(&self.0))
+    }
+}
+
+/// Version of the structured error payload carried by error responses.
+pub const ERROR_PAYLOAD_VERSION: u8 = 1;
+
+/// Machine readable category of an [`ApiError`].
+#[allow(dead_code)]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    InvalidRequest,
+    InvalidArgs,
+    Unauthorized,
+    Forbidden,
+    NotFound,
+    Conflict,
+    PayloadTooLarge,
+    NotSupported,
+    Unavailable,
+    Timeout,
+    Internal,
+    Handler,
+}
+
+impl ErrorKind {
+    /// Every kind, in declaration order.
+    pub const ALL: [ErrorKind; 12] = [
+        ErrorKind::InvalidRequest,
+        ErrorKind::InvalidArgs,
+        ErrorKind::Unauthorized,
+        ErrorKind::Forbidden,
+        ErrorKind::NotFound,
+        ErrorKind::Conflict,
+        ErrorKind::PayloadTooLarge,
+        ErrorKind::NotSupported,
+        ErrorKind::Unavailable,
+        ErrorKind::Timeout,
+        ErrorKind::Internal,
+        ErrorKind::Handler,
+    ];
+
+    /// Default response code for this kind.
+    pub fn code(self) -> Code {
+        match self {
+            ErrorKind::InvalidRequest => 400,
+            ErrorKind::InvalidArgs => 422,
+            ErrorKind::Unauthorized => 401,
+            ErrorKind::Forbidden => 403,
+            ErrorKind::NotFound => 404,
+            ErrorKind::Conflict => 409,
+            ErrorKind::PayloadTooLarge => 413,
+            ErrorKind::NotSupported => 501,
+            ErrorKind::Unavailable => 503,
+            ErrorKind::Timeout => 504,
+            ErrorKind::Internal => 500,
+            ErrorKind::Handler => -1,
+        }
+    }
+
+    pub fn from_code(code: Code) -> Self {
+        match code {
+            400 => ErrorKind::InvalidRequest,
+            422 => ErrorKind::InvalidArgs,
+            401 => ErrorKind::Unauthorized,
+            403 => ErrorKind::Forbidden,
+            404 => ErrorKind::NotFound,
+            409 => ErrorKind::Conflict,
+            413 => ErrorKind::PayloadTooLarge,
+            501 => ErrorKind::NotSupported,
+            503 => ErrorKind::Unavailable,
+            504 => ErrorKind::Timeout,
+            -1 => ErrorKind::Handler,
+            _ => ErrorKind::Internal,
+        }
+    }
+}
+
+/// Structured error sent in the data slot of an error [`ApiResponse`].
+///
+/// The response keeps its `[id, code, message, data]` layout, so clients that
+/// only read `message` keep working; newer clients inspect `data.kind`.
+#[derive(Serialize, Clone, Debug)]
+pub struct ApiError {
+    pub version: u8,
+    pub code: Code,
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<Value>,
+}
+
+impl ApiError {
+    #[allow(dead_code)]
+    pub fn new<S: Into<String>>(kind: ErrorKind, message: S) -> Self {
+        Self::with_code(kind.code(), kind, message)
+    }
+    pub fn with_code<S: Into<String>>(code: Code, kind: ErrorKind, message: S) -> Self {
+        Self {
+            version: ERROR_PAYLOAD_VERSION,
+            code,
+            kind,
+            message: message.into(),
+            detail: None,
+        }
+    }
+    #[allow(dead_code)]
+    pub fn detail<D: Serialize>(mut self, detail: D) -> Self {
+        self.detail = serde_json::to_value(detail).ok();
+        self
+    }
+    #[allow(dead_code)]
+    pub fn invalid_args<E: std::fmt::Display>(err: E) -> Self {
+        Self::new(
+            ErrorKind::InvalidArgs,
+            format!("invalid arguments: {}", err),
+        )
+    }
+    #[allow(dead_code)]
+    pub fn not_supported<S: std::fmt::Display>(what: S) -> Self {
+        Self::new(
+            ErrorKind::NotSupported,
+            format!("{} is not supported on this platform", what),
+        )
+    }
+    pub fn into_response(self, id: u8) -> ApiResponse {
+        let code = self.code;
+        let message = self.message.clone();
+        ApiResponse(id, code, message, json!(self), None, None)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Returned by handlers that answer later through the sender taken with
+/// [`App::responder`], e.g. from a webview script callback.
+#[derive(Debug)]
+pub struct Deferred;
+
+impl std::fmt::Display for Deferred {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "response deferred")
+    }
+}
+
+impl std::error::Error for Deferred {}
 #[allow(dead_code)]
 pub type ApiInstance = std::pin::Pin<
     Box<
@@ -59,13 +342,96 @@ pub type ApiInstance = std::pin::Pin<
             ApiRequest,
             &FrameWindowTarget,
             &mut tao::event_loop::ControlFlow,
-        ) -> Result<ApiResponse>,
+        ) -> Result<Option<ApiResponse>>,
     >,
 >;
+/// Built-in request answered by the manager itself with the sorted names of
+/// all registered APIs.
+pub const LIST_APIS_API: &str = "system.listApis";
+
+/// Built-in request answered by the manager itself with a snapshot of the
+/// bridge [`Metrics`](crate::metrics::Metrics).
+pub const METRICS_API: &str = "system.metrics";
+
+/// Version of the connection protocol spoken by this build.
+///
+/// Raised whenever framing, the handshake or the request and response
+/// layout change incompatibly.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// First message of a `system.handshake`: `[id, "system.handshake", [hello]]`.
+#[derive(Deserialize, Serialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeRequest {
+    /// Protocol version the client speaks, see [`PROTOCOL_VERSION`].
+    pub protocol: u32,
+    /// Codec the client wants for the following frames (default `json`).
+    #[serde(default)]
+    pub codec: Option<pyorion_options::runtime::Codec>,
+    /// Shared secret, required when the server has one configured.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Whether the client accepts binary response parts.
+    #[serde(default)]
+    pub binary: Option<bool>,
+}
+
+/// Answer to a `system.handshake`, describing what the server offers.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct HandshakeInfo {
+    /// Version of the running binary, as reported to Python.
+    pub version: &'static str,
+    pub protocol: u32,
+    /// Codecs this build can speak; only `codec` is in use on this server.
+    pub codecs: Vec<pyorion_options::runtime::Codec>,
+    pub codec: pyorion_options::runtime::Codec,
+    /// Whether binary response parts are sent on this connection.
+    pub binary: bool,
+    /// Namespaces with at least one enabled API, e.g. `window`, `fs`.
+    pub namespaces: Vec<String>,
+}
+
+impl HandshakeInfo {
+    /// Checks `hello` against the server and describes the connection, or
+    /// fails with `400` on a protocol or codec mismatch.
+    pub fn negotiate(
+        hello: &HandshakeRequest,
+        options: &pyorion_options::runtime::RuntimeOptions,
+    ) -> std::result::Result<Self, ApiError> {
+        if hello.protocol != PROTOCOL_VERSION {
+            return Err(
+                ApiError::new(ErrorKind::InvalidRequest, "protocol mismatch")
+                    .detail(json!({ "expected": PROTOCOL_VERSION, "got": hello.protocol })),
+            );
+        }
+        let codec = options.codec();
+        if hello.codec.unwrap_or_default() != codec {
+            return Err(ApiError::new(ErrorKind::InvalidRequest, "codec mismatch")
+                .detail(json!({ "expected": codec })));
+        }
+        Ok(Self {
+            version: crate::get_pyorion_version(),
+            protocol: PROTOCOL_VERSION,
+            codecs: vec![
+                pyorion_options::runtime::Codec::Json,
+                pyorion_options::runtime::Codec::MsgPack,
+            ],
+            codec,
+            binary: hello.binary.unwrap_or(false),
+            namespaces: options.api_namespaces.clone(),
+        })
+    }
+}
+
 #[allow(dead_code)]
 pub struct ApiManager {
     ctx: Option<Weak<App>>,
     api_instance: HashMap<String, ApiInstance>,
+    /// Calls per registered API name.
+    calls: HashMap<String, u64>,
+    /// APIs left out by the runtime options are never registered.
+    filter: pyorion_options::runtime::ApiFilter,
 }
 
 impl ApiManager {
@@ -74,6 +440,8 @@ impl ApiManager {
         let _self = Self {
             ctx: None,
             api_instance: HashMap::new(),
+            calls: HashMap::new(),
+            filter: Default::default(),
         };
         crate::utils::arc_mut(_self)
     }
@@ -81,6 +449,10 @@ impl ApiManager {
     pub fn bind_app_context(&mut self, ctx: &Arc<App>) {
         self.ctx = Some(Arc::downgrade(ctx));
     }
+    /// Restricts which APIs later [`ApiManager::register_api`] calls add.
+    pub fn set_filter(&mut self, filter: pyorion_options::runtime::ApiFilter) {
+        self.filter = filter;
+    }
     #[allow(dead_code)]
     pub fn register_api<S: Into<String>, T: Serialize + 'static>(
         &mut self,
@@ -92,33 +464,183 @@ impl ApiManager {
             &mut tao::event_loop::ControlFlow,
         ) -> Result<T>,
     ) {
+        let name = name.into();
+        if !self.filter.permits(&name) {
+            tracing::debug!(api = %name, "api disabled by configuration");
+            return;
+        }
         let api_instance: ApiInstance = Box::pin(move |ctx: Arc<App>, request, target, flow| {
             let result = api_func(ctx, request.clone(), target, flow);
             let response = match result {
-                Ok(data) => request.ok(data),
-                Err(err) => request.err(-1, err.to_string()),
+                Ok(mut data) => {
+                    match (&mut data as &mut dyn std::any::Any).downcast_mut::<Binary>() {
+                        Some(Binary(bytes)) => request.binary(std::mem::take(bytes)),
+                        None => request.ok(data),
+                    }
+                }
+                Err(err) if err.is::<Deferred>() => return Ok(None),
+                Err(err) => match err.downcast::<ApiError>() {
+                    Ok(api_err) => request.fail(api_err),
+                    Err(err) => request.err(ErrorKind::Handler.code(), err.to_string()),
+                },
             };
 
-            Ok(response)
+            Ok(Some(response))
         });
 
-        self.api_instance.insert(name.into(), api_instance);
+        self.api_instance.insert(name, api_instance);
+    }
+    /// Names of all registered APIs, including the built-ins, sorted.
+    pub fn api_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.api_instance.keys().map(String::as_str).collect();
+        names.push(LIST_APIS_API);
+        names.push(METRICS_API);
+        names.sort_unstable();
+        names
     }
+    /// Namespaces of the registered APIs (the part before the first `.`),
+    /// sorted and without duplicates.
+    pub fn namespaces(&self) -> Vec<String> {
+        let mut namespaces: Vec<String> = self
+            .api_instance
+            .keys()
+            .map(|name| name.split('.').next().unwrap_or(name).to_string())
+            .collect();
+        namespaces.sort_unstable();
+        namespaces.dedup();
+        namespaces
+    }
+    /// Answers `req` when no handler is involved: the built-ins, or `404`
+    /// echoing the name when nothing is registered under it.
+    pub fn answer_unhandled(&self, req: &ApiRequest) -> Option<ApiResponse> {
+        if req.1 == LIST_APIS_API {
+            // Answered here: a handler cannot see the manager while it is locked
+            return Some(req.ok(self.api_names()));
+        }
+        if req.1 == METRICS_API {
+            return Some(req.ok(crate::metrics::METRICS.snapshot(&self.calls)));
+        }
+        if self.api_instance.contains_key(&req.1) {
+            return None;
+        }
+        Some(
+            req.fail(
+                ApiError::new(ErrorKind::NotFound, format!("unknown api: {}", req.1))
+                    .detail(json!({ "api": req.1 })),
+            ),
+        )
+    }
+    /// Runs the handler registered for `req`.
+    ///
+    /// `None` means the handler deferred its answer.
     #[allow(dead_code)]
     pub fn call(
         &mut self,
         req: ApiRequest,
         target: &FrameWindowTarget,
         flow: &mut tao::event_loop::ControlFlow,
-    ) -> anyhow::Result<ApiResponse> {
-        if let Some(handler) = self.api_instance.get(&req.1) {
-            if let Some(ctx) = self.ctx.as_ref().and_then(|w| w.upgrade()) {
-                handler(ctx, req.clone(), target, flow)
-            } else {
-                Err(anyhow::anyhow!("App reference not available"))
+    ) -> anyhow::Result<Option<ApiResponse>> {
+        if let Some(resp) = self.answer_unhandled(&req) {
+            return Ok(Some(resp));
+        }
+        let Some(ctx) = self.ctx.as_ref().and_then(|w| w.upgrade()) else {
+            return Err(anyhow::anyhow!("App reference not available"));
+        };
+        *self.calls.entry(req.1.clone()).or_default() += 1;
+        let started = std::time::Instant::now();
+        let result = self.api_instance[&req.1](ctx, req.clone(), target, flow);
+        crate::metrics::METRICS.handler_ran(started.elapsed());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::UserEvent;
+
+    /// Collects the events a handler emits.
+    struct Recorder(std::sync::mpsc::Sender<UserEvent>);
+
+    impl EventSink for Recorder {
+        fn send(&self, event: UserEvent) {
+            let _ = self.0.send(event);
+        }
+    }
+
+    impl RequestContext {
+        /// A context for `req` without an app behind it.
+        pub(crate) fn detached(req: &ApiRequest, events: impl EventSink + 'static) -> Self {
+            Self {
+                id: req.0,
+                api: req.1.clone(),
+                label: "main".to_string(),
+                // Never handed to tao, only carried along
+                window_id: unsafe { tao::window::WindowId::dummy() },
+                events: Box::new(events),
             }
-        } else {
-            Err(anyhow::anyhow!("Unknown method: {}", req.1))
         }
     }
+
+    #[tokio::test]
+    async fn progress_precedes_the_deferred_response() {
+        let (events_tx, events) = std::sync::mpsc::channel();
+        let req: ApiRequest = serde_json::from_str(r#"[9, "fs.copy", []]"#).unwrap();
+        let ctx = RequestContext::detached(&req, Recorder(events_tx));
+        let (responder, answer) = tokio::sync::oneshot::channel();
+
+        // Shaped like a deferred handler: report from a worker, then answer
+        tokio::task::spawn_blocking(move || {
+            for percent in [25, 50, 150] {
+                ctx.progress(percent, Some("copying"));
+            }
+            let _ = responder.send(req.ok("done"));
+        });
+
+        let resp = answer.await.unwrap();
+        assert_eq!(resp.0, 9);
+        assert_eq!(resp.1, 0);
+        assert_eq!(resp.3, json!("done"));
+
+        let progress: Vec<Value> = events
+            .try_iter()
+            .map(|event| {
+                let UserEvent::Emit(name, payload) = event else {
+                    panic!("expected an emitted event");
+                };
+                assert_eq!(name, "progress");
+                payload
+            })
+            .collect();
+        assert_eq!(progress.len(), 3);
+        assert_eq!(progress[0]["requestId"], 9);
+        assert_eq!(progress[0]["api"], "fs.copy");
+        assert_eq!(progress[1]["percent"], 50);
+        assert_eq!(progress[1]["message"], "copying");
+        assert_eq!(progress[2]["percent"], 100);
+    }
+
+    #[test]
+    fn denied_namespace_is_not_registered() {
+        let manager = ApiManager::new();
+        let mut manager = manager.lock().unwrap();
+        manager.set_filter(pyorion_options::runtime::ApiFilter {
+            allow: Vec::new(),
+            deny: vec!["clipboard.*".to_string()],
+        });
+        crate::api::register_api_instances(&mut manager);
+
+        let req: ApiRequest = serde_json::from_str(r#"[4, "clipboard.setText", ["x"]]"#).unwrap();
+        let resp = manager.answer_unhandled(&req).unwrap();
+        assert_eq!(resp.0, 4);
+        assert_eq!(resp.1, 404);
+        assert!(!manager
+            .api_names()
+            .iter()
+            .any(|name| name.starts_with("clipboard.")));
+
+        // Other namespaces are still served by their handlers
+        let req: ApiRequest = serde_json::from_str(r#"[5, "window.set_title", ["x"]]"#).unwrap();
+        assert!(manager.answer_unhandled(&req).is_none());
+    }
 }
@@ -0,0 +1,47 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Drag regions for custom title bars.
+ *
+ * Pressing the primary button on an element marked with
+ * `data-pyorion-drag-region` or the CSS `app-region: drag` moves the window.
+ * Interactive elements and anything marked `app-region: no-drag` or
+ * `data-pyorion-drag-region="false"` inside a region keep their clicks.
+ */
+(function () {
+  const INTERACTIVE = "a, button, input, select, textarea, label, [contenteditable]";
+
+  function region(el) {
+    for (; el && el.nodeType === 1; el = el.parentElement) {
+      const marker = el.getAttribute("data-pyorion-drag-region");
+      if (marker !== null) {
+        return marker !== "false";
+      }
+      const style = window.getComputedStyle(el);
+      const value = style.getPropertyValue("app-region")
+        || style.getPropertyValue("-webkit-app-region");
+      if (value === "drag") {
+        return true;
+      }
+      if (value === "no-drag") {
+        return false;
+      }
+    }
+    return false;
+  }
+
+  document.addEventListener("mousedown", (event) => {
+    if (event.button !== 0 || event.detail > 1) {
+      return;
+    }
+    if (event.target.closest && event.target.closest(INTERACTIVE)) {
+      return;
+    }
+    if (region(event.target)) {
+      event.preventDefault();
+      window.ipc.postMessage("pyorion:drag");
+    }
+  });
+})();
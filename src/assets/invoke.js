@@ -12,9 +12,16 @@
  *  - Auto reconnect with configurable interval.
  *  - Unique ID mapping for result/error callbacks.
  *  - Automatic cleanup of one-time callbacks.
- *  - Global `window.invoke` helper for command dispatch.
+ *  - Timeout for calls that are never answered.
+ *  - Global `window.pyorion.invoke` (and `window.invoke`) helper for
+ *    command dispatch.
  */
 (function () {
+  // The socket script may expose the connection under another name
+  const PyOrionConnections = window.__PYORION_CONNECTIONS__;
+
+  /** Milliseconds an invoke waits for its answer unless told otherwise. */
+  const DEFAULT_TIMEOUT = 30000;
 
   /**
    * Generate a random unique identifier.
@@ -96,18 +103,34 @@ function jsonMakeObjectSafe(obj) {
    *
    * @param {string} cmd - Command name.
    * @param {any} [args] - Payload arguments (any serializable type).
+   * @param {{timeout?: number}} [options] - `timeout` in milliseconds
+   *   (default 30000, `0` waits forever).
    * @returns {Promise<any>} Resolves with result of any type, rejects with error.
    */
-  function invoke(cmd, args) {
+  function invoke(cmd, args, options) {
     return new Promise((resolve, reject) => {
-      if (!PyOrionConnections.is_connected()) {
+      if (!PyOrionConnections || !PyOrionConnections.is_connected()) {
         reject(new Error("Socket is not connected or unavailable!"));
         return;
       }
       const py_args = args ?? {};
-
-      const result_id = transformCallback((result) => resolve(result), true);
-      const error_id = transformCallback((error) => reject(error), true);
+      const timeout = options?.timeout ?? DEFAULT_TIMEOUT;
+
+      let timer = null;
+      const settle = (callback) => (value) => {
+        clearTimeout(timer);
+        Reflect.deleteProperty(window, `_${result_id}`);
+        Reflect.deleteProperty(window, `_${error_id}`);
+        callback(value);
+      };
+      const result_id = transformCallback(settle(resolve), true);
+      const error_id = transformCallback(settle(reject), true);
+      if (timeout > 0) {
+        timer = setTimeout(
+          () => settle(reject)(new Error(`invoke ${cmd} timed out after ${timeout} ms`)),
+          timeout
+        );
+      }
 
       const message = {
         cmd,
@@ -120,7 +143,7 @@ function jsonMakeObjectSafe(obj) {
     });
   }
 
-PyOrionConnections.on("message", (raw) => {
+PyOrionConnections && PyOrionConnections.on("message", (raw) => {
     try {
       // console.log(raw);
       const data = jsonMakeObjectSafe(JSON.parse(raw));
@@ -141,6 +164,65 @@ PyOrionConnections.on("message", (raw) => {
 });
 
 
+  /**
+   * Listeners for native events, keyed by event name.
+   */
+  const nativeListeners = {};
+
+  /**
+   * Listen for a native event emitted by the backend (e.g. `themeChanged`).
+   *
+   * @param {string} event - Event name.
+   * @param {Function} handler - Called with the event payload.
+   * @returns {Function} Function removing the listener again.
+   */
+  function listen(event, handler) {
+    (nativeListeners[event] = nativeListeners[event] || []).push(handler);
+    return () => {
+      nativeListeners[event] = nativeListeners[event].filter(h => h !== handler);
+    };
+  }
+
+  /** Most events held back while the socket is not open; the oldest go first. */
+  const MAX_PENDING_EVENTS = 256;
+
+  /**
+   * Events raised before the socket to Python is open, such as `ready`
+   * right after the first page load, in the order they were raised.
+   */
+  const pendingEvents = [];
+
+  /**
+   * Send a native event to Python, or hold it until the socket opens.
+   *
+   * @param {object} message - `{ event, payload, label }`.
+   */
+  function forward(message) {
+    if (PyOrionConnections.is_connected()) {
+      PyOrionConnections.send(message);
+      return;
+    }
+    if (pendingEvents.length >= MAX_PENDING_EVENTS) pendingEvents.shift();
+    pendingEvents.push(message);
+  }
+
+  PyOrionConnections && PyOrionConnections.on("open", () => {
+    while (pendingEvents.length && PyOrionConnections.is_connected()) {
+      PyOrionConnections.send(pendingEvents.shift());
+    }
+  });
+
+  // Native events are dispatched as `pyorion:event` and forwarded to Python.
+  window.addEventListener("pyorion:event", (e) => {
+    const { event, payload, label } = e.detail || {};
+    (nativeListeners[event] || []).forEach(handler => handler(payload));
+    if (PyOrionConnections) {
+      forward({ event, payload, label });
+    }
+  });
+
   // Expose invoke globally
+  window.pyorion = Object.assign(window.pyorion || {}, { invoke, listen });
   window.invoke = invoke;
+  window.listen = listen;
 })();
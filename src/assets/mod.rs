@@ -2,9 +2,80 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use serde::Deserialize;
+pub mod protocol;
+pub mod store;
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
 use serialize_to_javascript::{default_template, Template};
 
+/// TLS settings for the frontend WebSocket connection.
+///
+/// The webview performs the TLS handshake itself and validates the backend
+/// certificate against the platform trust store. Settings a platform's
+/// webview cannot apply fail at startup instead of being ignored, as do
+/// unknown fields.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TlsConfig {
+    #[serde(default)]
+    enabled: bool,
+
+    /// PEM file with a certificate trusted for the URL host on top of the
+    /// system trust store. WebKitGTK pins exactly this certificate, so it
+    /// has to be the one the backend presents, e.g. a self-signed one.
+    /// Linux only; elsewhere install it in the system trust store.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ca_cert: Option<std::path::PathBuf>,
+
+    /// Accept any certificate (Linux and Windows). Turns off protection
+    /// against a man in the middle, so it also needs `allowInsecure`.
+    #[serde(default)]
+    insecure_skip_verify: bool,
+
+    /// Explicit opt-in required before `insecureSkipVerify` is accepted.
+    #[serde(default)]
+    allow_insecure: bool,
+}
+
+impl TlsConfig {
+    pub fn ca_cert(&self) -> Option<&std::path::Path> {
+        self.ca_cert.as_deref()
+    }
+
+    pub fn insecure_skip_verify(&self) -> bool {
+        self.insecure_skip_verify
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.insecure_skip_verify && !self.allow_insecure {
+            bail!("TLS insecureSkipVerify turns off certificate checks and needs allowInsecure as well");
+        }
+        if self.insecure_skip_verify && cfg!(target_os = "macos") {
+            bail!("TLS insecureSkipVerify is not supported by WKWebView");
+        }
+        if let Some(path) = &self.ca_cert {
+            if !cfg!(target_os = "linux") {
+                bail!(
+                    "TLS caCert is only supported with WebKitGTK; install {} in the system trust store instead",
+                    path.display()
+                );
+            }
+            let mut reader = std::io::BufReader::new(std::fs::File::open(path).map_err(|e| {
+                anyhow::anyhow!("Cannot read TLS caCert {}: {}", path.display(), e)
+            })?);
+            if rustls_pemfile::certs(&mut reader)
+                .next()
+                .transpose()?
+                .is_none()
+            {
+                bail!("No PEM certificate found in TLS caCert {}", path.display());
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Deserialize, Template, Debug, Clone)]
 #[default_template("pyorion_socket.js")]
 pub struct WebSocketConfig {
@@ -13,11 +84,195 @@ pub struct WebSocketConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     protocols: Option<Vec<String>>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", alias = "autoReconnect")]
     auto_reconnect: Option<bool>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Delay before the first reconnect attempt in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none", alias = "reconnectInterval")]
     reconnect_interval: Option<u64>,
+
+    /// Upper bound for the exponentially growing reconnect delay.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        alias = "reconnectMaxInterval"
+    )]
+    reconnect_max_interval: Option<u64>,
+
+    /// Factor applied to the delay after every failed attempt.
+    #[serde(skip_serializing_if = "Option::is_none", alias = "reconnectBackoff")]
+    reconnect_backoff: Option<f64>,
+
+    /// Attempts before giving up; unlimited when omitted.
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        alias = "maxReconnectAttempts"
+    )]
+    max_reconnect_attempts: Option<u32>,
+
+    /// Milliseconds between heartbeat pings; `0` disables them (default `15000`).
+    #[serde(skip_serializing_if = "Option::is_none", alias = "heartbeatInterval")]
+    heartbeat_interval: Option<u64>,
+
+    /// Milliseconds to wait for the pong before the peer counts as lost
+    /// (default `5000`).
+    #[serde(skip_serializing_if = "Option::is_none", alias = "heartbeatTimeout")]
+    heartbeat_timeout: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsConfig>,
+
+    /// Name of the `window` property holding the connection object
+    /// (default `PyOrionConnections`); must be a plain JS identifier.
+    #[serde(skip_serializing_if = "Option::is_none", alias = "globalName")]
+    global_name: Option<String>,
+}
+
+impl WebSocketConfig {
+    /// Validates the TLS section and upgrades the URL to `wss://` when enabled.
+    pub fn with_tls(mut self) -> anyhow::Result<Self> {
+        let Some(tls) = self.tls() else {
+            return Ok(self);
+        };
+        tls.validate()?;
+        self.url = if let Some(rest) = self.url.strip_prefix("ws://") {
+            format!("wss://{}", rest)
+        } else if self.url.starts_with("wss://") {
+            self.url
+        } else {
+            bail!("TLS requires a ws:// or wss:// URL, got {}", self.url);
+        };
+        Ok(self)
+    }
+
+    /// The TLS settings, if TLS is enabled.
+    pub fn tls(&self) -> Option<&TlsConfig> {
+        self.tls.as_ref().filter(|tls| tls.enabled)
+    }
+}
+
+impl WebSocketConfig {
+    /// Host of the WebSocket URL, without brackets around IPv6 addresses.
+    pub fn host(&self) -> Option<String> {
+        let url = url::Url::parse(&self.url).ok()?;
+        Some(match url.host()? {
+            url::Host::Ipv6(addr) => addr.to_string(),
+            host => host.to_string(),
+        })
+    }
+
+    /// Port of the WebSocket URL, including the scheme default.
+    pub fn port(&self) -> Option<u16> {
+        url::Url::parse(&self.url).ok()?.port_or_known_default()
+    }
+
+    /// Rejects URLs the webview cannot connect to.
+    ///
+    /// A bare `host:port` gets `ws://` prepended; any scheme other than `ws`
+    /// or `wss` is an error rather than a connection that silently fails.
+    /// Hosts may be names, IPv4 addresses or bracketed IPv6 addresses
+    /// (`ws://[::1]:8765`). Port `0` asks the OS for a free port; the server
+    /// has to be bound and the real port filled in before the webframe is
+    /// started.
+    pub fn validate(mut self) -> anyhow::Result<Self> {
+        if !self.url.contains("://") {
+            self.url = format!("ws://{}", self.url);
+        }
+        let url = url::Url::parse(&self.url).map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid WebSocket URL {}: {} (IPv6 hosts must be bracketed, e.g. ws://[::1]:8765)",
+                self.url,
+                e
+            )
+        })?;
+        if !matches!(url.scheme(), "ws" | "wss") {
+            bail!(
+                "WebSocket URL {} must use ws:// or wss://, not {}://",
+                self.url,
+                url.scheme()
+            );
+        }
+        if url.host().is_none() {
+            bail!("WebSocket URL {} has no host", self.url);
+        }
+        if url.port() == Some(0) {
+            bail!(
+                "WebSocket URL {} uses port 0; pass the port the server was bound to",
+                self.url
+            );
+        }
+        if let Some(name) = &self.global_name {
+            if !is_js_identifier(name) {
+                bail!(
+                    "WebSocket globalName {:?} is not a valid JavaScript identifier",
+                    name
+                );
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// Words that cannot name a binding in strict mode code.
+const JS_RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Whether `name` is an ASCII JavaScript identifier and no reserved word.
+fn is_js_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    (first.is_ascii_alphabetic() || first == '_' || first == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        && !JS_RESERVED.contains(&name)
 }
 
 pub fn websocket_config(cfg: WebSocketConfig) -> anyhow::Result<String> {
@@ -28,4 +283,111 @@ pub fn websocket_config(cfg: WebSocketConfig) -> anyhow::Result<String> {
     Ok(serialized.into_string())
 }
 
+/// Source of the `window.pyorion.invoke` helper, injected when
+/// `WebViewOptions.invokeHelper` is `true`; exported to Python as
+/// `INVOKE_SCRIPT` to build a customized copy from.
 pub static _COMMAND_SCRIPT: &str = include_str!("./invoke.js");
+
+/// Starts window drags from marked elements, see `enableDragRegions`.
+pub static DRAG_REGION_SCRIPT: &str = include_str!("./drag_region.js");
+
+/// IPC message the drag region script posts to start dragging the window.
+pub const DRAG_MESSAGE: &str = "pyorion:drag";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(global_name: &str) -> anyhow::Result<WebSocketConfig> {
+        serde_json::from_value::<WebSocketConfig>(serde_json::json!({
+            "url": "ws://127.0.0.1:8765/ws",
+            "globalName": global_name,
+        }))?
+        .validate()
+    }
+
+    #[test]
+    fn socket_script_uses_the_global_name() {
+        let script = websocket_config(config("myConnection").unwrap()).unwrap();
+        assert!(script.contains("\"myConnection\""));
+        assert!(!script.contains("__TEMPLATE_global_name__"));
+        // invoke.js keeps finding the connection under its fixed name
+        assert!(script.contains("window.__PYORION_CONNECTIONS__ = PyOrionConnections"));
+    }
+
+    #[test]
+    fn global_name_must_be_an_identifier() {
+        for name in ["$pyorion", "_conn2", "PyOrionConnections"] {
+            assert!(config(name).is_ok(), "{} should be accepted", name);
+        }
+        for name in [
+            "",
+            "2conn",
+            "my-conn",
+            "a.b",
+            "x;alert(1)",
+            "class",
+            "ünïcode",
+        ] {
+            assert!(config(name).is_err(), "{:?} should be rejected", name);
+        }
+    }
+
+    fn tls(tls: serde_json::Value) -> anyhow::Result<WebSocketConfig> {
+        serde_json::from_value::<WebSocketConfig>(serde_json::json!({
+            "url": "ws://example.com:8765/ws",
+            "tls": tls,
+        }))?
+        .with_tls()
+    }
+
+    #[test]
+    fn tls_upgrades_the_url_and_rejects_unknown_fields() {
+        let cfg = tls(serde_json::json!({ "enabled": true })).unwrap();
+        assert_eq!(cfg.url, "wss://example.com:8765/ws");
+        assert_eq!(cfg.host().as_deref(), Some("example.com"));
+
+        assert!(tls(serde_json::json!({ "enabled": true, "verify": false })).is_err());
+    }
+
+    #[test]
+    fn insecure_skip_verify_needs_the_opt_in() {
+        let err =
+            tls(serde_json::json!({ "enabled": true, "insecureSkipVerify": true })).unwrap_err();
+        assert!(err.to_string().contains("allowInsecure"), "{err}");
+
+        let cfg = tls(serde_json::json!({
+            "enabled": true,
+            "insecureSkipVerify": true,
+            "allowInsecure": true,
+        }));
+        assert_eq!(cfg.is_ok(), !cfg!(target_os = "macos"));
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn ca_cert_must_hold_a_pem_certificate() {
+        let fixture = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/backend.pem");
+        let cfg = tls(serde_json::json!({ "enabled": true, "caCert": fixture })).unwrap();
+        assert_eq!(
+            cfg.tls().unwrap().ca_cert(),
+            Some(std::path::Path::new(fixture))
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.pem");
+        assert!(tls(serde_json::json!({ "enabled": true, "caCert": missing })).is_err());
+        let empty = dir.path().join("empty.pem");
+        std::fs::write(&empty, "not a certificate").unwrap();
+        assert!(tls(serde_json::json!({ "enabled": true, "caCert": empty })).is_err());
+    }
+
+    #[test]
+    fn host_drops_the_ipv6_brackets() {
+        let cfg = serde_json::from_value::<WebSocketConfig>(serde_json::json!({
+            "url": "ws://[::1]:8765/ws",
+        }))
+        .unwrap();
+        assert_eq!(cfg.host().as_deref(), Some("::1"));
+    }
+}
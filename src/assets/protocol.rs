@@ -0,0 +1,130 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use wry::http::{header, HeaderMap, Request, Response, StatusCode};
+
+use super::store::{content_type, AssetStore};
+
+/// Scheme the bundled frontend is served under (`app://localhost/...`).
+pub const APP_SCHEME: &str = "app";
+
+/// URL the webview navigates to for the root of [`APP_SCHEME`].
+///
+/// Windows and Android expose custom protocols as `http://<scheme>.localhost`.
+pub fn app_url() -> String {
+    if cfg!(any(target_os = "windows", target_os = "android")) {
+        format!("http://{}.localhost/", APP_SCHEME)
+    } else {
+        format!("{}://localhost/", APP_SCHEME)
+    }
+}
+
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn status(code: StatusCode, body: &str) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(code)
+        .header(header::CONTENT_TYPE, "text/plain")
+        .body(body.as_bytes().to_vec())
+        .unwrap()
+}
+
+/// Registers the `app://` protocol backed by `store` and navigates to it.
+///
+/// `headers` are added to every response, overriding the defaults; this is
+/// where CORS or `Cross-Origin-Opener-Policy`/`-Embedder-Policy` go.
+pub fn register<'a>(
+    builder: wry::WebViewBuilder<'a>,
+    store: AssetStore,
+    index: String,
+    headers: HeaderMap,
+) -> wry::WebViewBuilder<'a> {
+    builder.with_asynchronous_custom_protocol(
+        APP_SCHEME.into(),
+        move |_webview_id, request, responder| {
+            let mut response = serve(&request, &store, &index);
+            for (name, value) in &headers {
+                response.headers_mut().insert(name, value.clone());
+            }
+            responder.respond(response);
+        },
+    )
+}
+
+/// Parses a single `bytes=` range against a body of `len` bytes.
+///
+/// `Some(None)` means the header was present but unsatisfiable.
+fn parse_range(value: &str, len: u64) -> Option<Option<(u64, u64)>> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    // Multipart ranges are rare for media; serve the first one
+    let spec = spec.split(',').next()?.trim();
+    let (start, end) = spec.split_once('-')?;
+    let range = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len.checked_sub(1)?)
+        }
+        (start, "") => (start.parse().ok()?, len.checked_sub(1)?),
+        (start, end) => (
+            start.parse().ok()?,
+            end.parse::<u64>().ok()?.min(len.checked_sub(1)?),
+        ),
+    };
+    Some((range.0 <= range.1 && range.0 < len).then_some(range))
+}
+
+/// Serves `request` from `store`.
+///
+/// `/` and directories resolve to `index`, unknown paths answer `404` and a
+/// `Range` header yields a `206` partial response, as media elements expect.
+pub fn serve(request: &Request<Vec<u8>>, store: &AssetStore, index: &str) -> Response<Vec<u8>> {
+    let found = percent_decode(request.uri().path().trim_start_matches('/'))
+        .and_then(|path| store.get(&path, index));
+    let Some((name, content)) = found else {
+        return status(StatusCode::NOT_FOUND, "Not Found");
+    };
+    let mime_type = content_type(&name);
+    let len = content.len() as u64;
+
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_range(value, len));
+
+    let builder = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+
+    match range {
+        None => builder.status(StatusCode::OK).body(content),
+        Some(Some((start, end))) => builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", start, end, len),
+            )
+            .body(content[start as usize..=end as usize].to_vec()),
+        Some(None) => builder
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new()),
+    }
+    .unwrap_or_else(|_| status(StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error"))
+}
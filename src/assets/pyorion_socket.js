@@ -51,12 +51,80 @@ function jsonMakeObjectSafe(obj) {
   var config = {
     protocols: __TEMPLATE_protocols__,
     reconnectInterval: __TEMPLATE_reconnect_interval__ || 3000,
-    autoReconnect: __TEMPLATE_auto_reconnect__ ?? true
+    reconnectMaxInterval: __TEMPLATE_reconnect_max_interval__ || 30000,
+    reconnectBackoff: __TEMPLATE_reconnect_backoff__ || 2,
+    maxReconnectAttempts: __TEMPLATE_max_reconnect_attempts__ ?? null,
+    autoReconnect: __TEMPLATE_auto_reconnect__ ?? true,
+    heartbeatInterval: __TEMPLATE_heartbeat_interval__ ?? 15000,
+    heartbeatTimeout: __TEMPLATE_heartbeat_timeout__ ?? 5000
   };
 
   var eventListeners = {};
   var reconnectTimer = null;
   var shouldReconnect = true;
+  var reconnectAttempts = 0;
+  var heartbeatTimer = null;
+  var pongTimer = null;
+
+  // Reports the socket state like any other native `connectionStateChanged` event
+  function notifyState(state, detail) {
+    var payload = Object.assign({ state: state, source: "websocket" }, detail || {});
+    window.dispatchEvent(new CustomEvent("pyorion:event", {
+      detail: { event: "connectionStateChanged", payload: payload }
+    }));
+  }
+
+  // Pings the peer; a missing pong means it is dead even if the socket looks open
+  function startHeartbeat() {
+    stopHeartbeat();
+    if (!config.heartbeatInterval) return;
+    heartbeatTimer = setInterval(function () {
+      if (!ws || ws.readyState !== WebSocket.OPEN || pongTimer) return;
+      ws.send(JSON.stringify({ type: "ping", ts: Date.now() }));
+      pongTimer = setTimeout(function () {
+        pongTimer = null;
+        window.dispatchEvent(new CustomEvent("pyorion:event", {
+          detail: {
+            event: "connectionLost",
+            payload: { source: "websocket", timeoutMs: config.heartbeatTimeout }
+          }
+        }));
+        // onclose takes care of reconnecting
+        ws.close(4000, "Heartbeat timeout");
+      }, config.heartbeatTimeout);
+    }, config.heartbeatInterval);
+  }
+
+  function stopHeartbeat() {
+    clearInterval(heartbeatTimer);
+    clearTimeout(pongTimer);
+    heartbeatTimer = null;
+    pongTimer = null;
+  }
+
+  function isPong(data) {
+    if (typeof data !== "string" || data.indexOf('"pong"') === -1) return false;
+    try {
+      var message = JSON.parse(data);
+      return message !== null && message.type === "pong";
+    } catch (_) {
+      return false;
+    }
+  }
+
+  function scheduleReconnect() {
+    if (config.maxReconnectAttempts !== null && reconnectAttempts >= config.maxReconnectAttempts) {
+      notifyState("disconnected", { attempt: reconnectAttempts });
+      return;
+    }
+    var delay = Math.min(
+      config.reconnectInterval * Math.pow(config.reconnectBackoff, reconnectAttempts),
+      config.reconnectMaxInterval
+    );
+    reconnectAttempts += 1;
+    notifyState("reconnecting", { attempt: reconnectAttempts, delayMs: delay });
+    reconnectTimer = setTimeout(PyOrionConnections.connect, delay);
+  }
 
   function addEventListener(event, listener) {
     if (!eventListeners[event]) {
@@ -96,13 +164,28 @@ function jsonMakeObjectSafe(obj) {
       ws = new WebSocket(__TEMPLATE_url__);
     }
 
-    ws.onopen = function (e) { dispatchEvent('open', e); };
-    ws.onmessage = function (e) { dispatchEvent('message', e.data); };
+    ws.onopen = function (e) {
+      reconnectAttempts = 0;
+      dispatchEvent('open', e);
+      notifyState("connected");
+      startHeartbeat();
+    };
+    ws.onmessage = function (e) {
+      if (isPong(e.data)) {
+        clearTimeout(pongTimer);
+        pongTimer = null;
+        return;
+      }
+      dispatchEvent('message', e.data);
+    };
     ws.onerror = function (e) { dispatchEvent('error', e); };
     ws.onclose = function (e) {
+      stopHeartbeat();
       dispatchEvent('close', e);
       if (shouldReconnect) {
-        reconnectTimer = setTimeout(PyOrionConnections.connect, config.reconnectInterval);
+        scheduleReconnect();
+      } else {
+        notifyState("disconnected");
       }
     };
   };
@@ -135,7 +218,9 @@ function jsonMakeObjectSafe(obj) {
   PyOrionConnections.offAll = removeAllEventListeners;
 
   if (!window) { window = {}; }
-  window.PyOrionConnections = PyOrionConnections;
+  window[__TEMPLATE_global_name__ || "PyOrionConnections"] = PyOrionConnections;
+  // Fixed name for the invoke helper, whatever the public one is
+  window.__PYORION_CONNECTIONS__ = PyOrionConnections;
 
   // === Automatisch Verbindung starten ===
   PyOrionConnections.connect();
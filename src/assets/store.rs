@@ -0,0 +1,111 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use anyhow::bail;
+use pyorion_options::window::AssetsOptions;
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::utils::MimeType;
+
+/// Default page for `/` and directory requests.
+pub const DEFAULT_INDEX: &str = "index.html";
+
+/// Source of the files served over the `app://` protocol.
+pub enum AssetStore {
+    /// Files read from disk below a base directory.
+    Directory(PathBuf),
+    /// Files of a ZIP bundle, held in memory.
+    Bundle(HashMap<String, Vec<u8>>),
+}
+
+impl AssetStore {
+    pub fn from_options(options: &AssetsOptions) -> anyhow::Result<Self> {
+        match (&options.dir, &options.bundle) {
+            (Some(dir), None) => Ok(Self::Directory(PathBuf::from(dir))),
+            (None, Some(bundle)) => Self::load_bundle(Path::new(bundle)),
+            (Some(_), Some(_)) => bail!("assets: set either dir or bundle, not both"),
+            (None, None) => bail!("assets: one of dir or bundle is required"),
+        }
+    }
+
+    /// Reads every file of the ZIP archive at `path` into memory.
+    pub fn load_bundle(path: &Path) -> anyhow::Result<Self> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+        let mut files = HashMap::new();
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if !entry.is_file() {
+                continue;
+            }
+            // `enclosed_name` drops entries trying to escape the archive root
+            let Some(name) = entry.enclosed_name() else {
+                continue;
+            };
+            let name = name.to_string_lossy().replace('\\', "/");
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut content)?;
+            files.insert(name, content);
+        }
+        Ok(Self::Bundle(files))
+    }
+
+    /// Looks up `path` (already percent-decoded, without leading `/`).
+    ///
+    /// Directories resolve to `index`. Returns the resolved name (for MIME
+    /// detection) and the content; `None` for unknown or escaping paths.
+    pub fn get(&self, path: &str, index: &str) -> Option<(String, Vec<u8>)> {
+        match self {
+            Self::Directory(root) => {
+                let root = root.canonicalize().ok()?;
+                let mut file = root.join(path).canonicalize().ok()?;
+                if file.is_dir() {
+                    file = file.join(index);
+                }
+                if !file.starts_with(&root) || !file.is_file() {
+                    return None;
+                }
+                let content = std::fs::read(&file).ok()?;
+                Some((file.to_string_lossy().into_owned(), content))
+            }
+            Self::Bundle(files) => {
+                if Path::new(path)
+                    .components()
+                    .any(|c| !matches!(c, Component::Normal(_)))
+                {
+                    return None;
+                }
+                let path = path.trim_end_matches('/');
+                let candidates = if path.is_empty() {
+                    vec![index.to_string()]
+                } else {
+                    vec![path.to_string(), format!("{}/{}", path, index)]
+                };
+                candidates
+                    .into_iter()
+                    .find_map(|name| files.get(&name).map(|c| (name, c.clone())))
+            }
+        }
+    }
+}
+
+/// Content type for `path` based on its extension.
+///
+/// Covers the usual web assets (html, js, css, wasm, svg, png, woff2, ...)
+/// and falls back to `application/octet-stream`.
+pub fn content_type(path: &str) -> String {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => match ext.to_ascii_lowercase().as_str() {
+            "htm" => MimeType::Html.to_string(),
+            ext => {
+                MimeType::parse_from_uri_with_fallback(&format!(".{}", ext), MimeType::OctetStream)
+                    .to_string()
+            }
+        },
+        None => MimeType::OctetStream.to_string(),
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use pyorion_options::runtime::Codec;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Serializes one frame body with `codec`.
+pub fn encode<T: Serialize>(codec: Codec, value: &T) -> anyhow::Result<Vec<u8>> {
+    Ok(match codec {
+        Codec::Json => serde_json::to_vec(value)?,
+        Codec::MsgPack => rmp_serde::to_vec(value)?,
+    })
+}
+
+/// Deserializes one frame body with `codec`.
+pub fn decode<T: DeserializeOwned>(codec: Codec, buf: &[u8]) -> anyhow::Result<T> {
+    Ok(match codec {
+        Codec::Json => serde_json::from_slice(buf)?,
+        Codec::MsgPack => rmp_serde::from_slice(buf)?,
+    })
+}
+
+/// Re-encodes a JSON document (as produced by Python) with `codec`.
+pub fn from_json(codec: Codec, json: &str) -> anyhow::Result<Vec<u8>> {
+    match codec {
+        Codec::Json => Ok(json.as_bytes().to_vec()),
+        _ => encode(codec, &serde_json::from_str::<serde_json::Value>(json)?),
+    }
+}
+
+/// Turns a frame encoded with `codec` back into a JSON document.
+pub fn to_json(codec: Codec, buf: &[u8]) -> anyhow::Result<String> {
+    match codec {
+        Codec::Json => Ok(String::from_utf8_lossy(buf).to_string()),
+        _ => Ok(serde_json::to_string(&decode::<serde_json::Value>(
+            codec, buf,
+        )?)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_manager::ApiRequest;
+
+    /// Encodes and decodes `value` with `codec`, returning the frame size.
+    fn round_trip(codec: Codec, value: &serde_json::Value) -> usize {
+        let frame = encode(codec, value).unwrap();
+        let decoded: serde_json::Value = decode(codec, &frame).unwrap();
+        assert_eq!(&decoded, value);
+        frame.len()
+    }
+
+    #[test]
+    fn one_megabyte_round_trip() {
+        let req: ApiRequest = serde_json::from_str(r#"[1, "fs.readFile", []]"#).unwrap();
+        let payload: Vec<u8> = (0..1 << 20).map(|i| (i % 256) as u8).collect();
+        let resp = serde_json::to_value(req.ok(payload)).unwrap();
+
+        let json = round_trip(Codec::Json, &resp);
+        let msgpack = round_trip(Codec::MsgPack, &resp);
+        // Byte arrays take 1-2 bytes per element in MessagePack, 2-4 in JSON
+        assert!(msgpack < json * 2 / 3, "{msgpack} vs {json}");
+    }
+}
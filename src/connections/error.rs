@@ -0,0 +1,73 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/// Why serving a client connection failed.
+#[derive(Debug)]
+pub enum ConnectionError {
+    /// Reading from or writing to the stream failed.
+    Io(std::io::Error),
+    /// A frame body could not be decoded with the negotiated codec.
+    Decode(anyhow::Error),
+    /// A response could not be encoded with the negotiated codec.
+    Encode(anyhow::Error),
+    /// The client failed the `system.auth` handshake.
+    Auth(&'static str),
+    /// The stream ended inside a frame.
+    Framing { expected: usize },
+    /// A frame announced more bytes than `maxRequestBytes` allows.
+    TooLarge { size: usize, max: usize },
+}
+
+impl ConnectionError {
+    /// Short category for logs and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConnectionError::Io(_) => "io",
+            ConnectionError::Decode(_) => "decode",
+            ConnectionError::Encode(_) => "encode",
+            ConnectionError::Auth(_) => "auth",
+            ConnectionError::Framing { .. } => "framing",
+            ConnectionError::TooLarge { .. } => "too_large",
+        }
+    }
+}
+
+impl std::fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionError::Io(e) => write!(f, "i/o error: {}", e),
+            ConnectionError::Decode(e) => write!(f, "failed to decode frame: {}", e),
+            ConnectionError::Encode(e) => write!(f, "failed to encode response: {}", e),
+            ConnectionError::Auth(reason) => write!(f, "authentication failed: {}", reason),
+            ConnectionError::Framing { expected } => {
+                write!(f, "stream ended inside a frame of {} bytes", expected)
+            }
+            ConnectionError::TooLarge { size, max } => {
+                write!(
+                    f,
+                    "frame of {} bytes exceeds the limit of {} bytes",
+                    size, max
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConnectionError::Io(e) => Some(e),
+            ConnectionError::Decode(e) | ConnectionError::Encode(e) => Some(e.as_ref()),
+            ConnectionError::Auth(_)
+            | ConnectionError::Framing { .. }
+            | ConnectionError::TooLarge { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConnectionError {
+    fn from(e: std::io::Error) -> Self {
+        ConnectionError::Io(e)
+    }
+}
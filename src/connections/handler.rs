@@ -4,65 +4,746 @@
 
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
-pub async fn handle_client<S>(
+use pyorion_options::runtime::{Codec, RuntimeOptions};
+
+use super::error::ConnectionError;
+use crate::api_manager::{
+    ApiError, ApiRequest, ApiResponse, ErrorKind, HandshakeInfo, HandshakeRequest,
+};
+
+/// Name of the handshake request a client must send first.
+///
+/// Required whenever a token or a non-JSON codec is configured. The frame is
+/// always JSON: `[id, "system.auth", [token, codec]]`. [`HANDSHAKE_API`] is
+/// accepted in its place.
+pub const AUTH_API: &str = "system.auth";
+
+/// Name of the handshake that also negotiates the protocol version.
+///
+/// The frame is always JSON: `[id, "system.handshake", [hello]]` with a
+/// [`HandshakeRequest`] as `hello`, answered with a [`HandshakeInfo`]. It
+/// stands in for [`AUTH_API`] where a handshake is required and may be sent
+/// as the first frame otherwise; a protocol or codec mismatch is answered
+/// with `400` and ends the connection.
+pub const HANDSHAKE_API: &str = "system.handshake";
+
+/// Name of the request that carries several requests in one frame.
+///
+/// The frame is `[id, "batch", [[request, ...], stopOnError]]` and is answered
+/// with `[id, 0, "ok", [response, ...]]`, one response per sub-request in
+/// order, each keeping its own id. By default every sub-request runs even if
+/// an earlier one failed; with `stopOnError` the batch ends after the first
+/// failure and the remaining sub-requests are left out of the answer.
+pub const BATCH_API: &str = "batch";
+
+/// Header bit marking a response frame followed by a binary part.
+///
+/// A frame is a little-endian `u32` length followed by the encoded body. When
+/// this bit is set in the length, the body is followed by a second
+/// little-endian `u32` length and that many raw bytes, which are the result of
+/// the response; its `data` holds their length. Only responses to clients
+/// that sent `true` as third handshake argument, `[token, codec, binary]`,
+/// carry the flag.
+pub const BINARY_FLAG: u32 = 1 << 31;
+
+/// Where decoded requests go to be answered through the pending map.
+///
+/// The event loop proxy in the app; anything else lets [`handle_client`] run
+/// over an in-memory stream without a window or event loop.
+pub trait RequestSink {
+    fn submit(&self, req: ApiRequest);
+}
+
+impl RequestSink for crate::utils::FrameEventLoopProxy {
+    fn submit(&self, req: ApiRequest) {
+        let _ = self.send_event(crate::utils::UserEvent::Request(req));
+    }
+}
+
+/// Reads one length-prefixed frame, `None` once the peer hung up.
+///
+/// A frame longer than `max` bytes is refused before its body is read.
+async fn read_frame<S>(stream: &mut S, max: usize) -> Result<Option<Vec<u8>>, ConnectionError>
+where
+    S: AsyncRead + Unpin,
+{
+    // === 1. Länge lesen ===
+    let mut len_buf = [0u8; 4];
+    if let Err(_) | Ok(0) = stream.read_exact(&mut len_buf).await {
+        return Ok(None); // Verbindung beendet
+    }
+    let len = (u32::from_le_bytes(len_buf) & !BINARY_FLAG) as usize;
+    if len > max {
+        return Err(ConnectionError::TooLarge { size: len, max });
+    }
+
+    // === 2. Nachricht lesen ===
+    let mut buf = vec![0u8; len];
+    match stream.read_exact(&mut buf).await {
+        Ok(_) => Ok(Some(buf)),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+            Err(ConnectionError::Framing { expected: len })
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn write_response<S>(
+    stream: &mut S,
+    codec: Codec,
+    resp: &ApiResponse,
+) -> Result<(), ConnectionError>
+where
+    S: AsyncWrite + Unpin,
+{
+    let resp_bytes = &super::codec::encode(codec, resp).map_err(ConnectionError::Encode)?;
+    let resp_len = resp_bytes.len() as u32;
+
+    match &resp.5 {
+        Some(binary) => {
+            stream
+                .write_all(&(resp_len | BINARY_FLAG).to_le_bytes())
+                .await?;
+            stream.write_all(resp_bytes).await?;
+            stream
+                .write_all(&(binary.len() as u32).to_le_bytes())
+                .await?;
+            stream.write_all(binary).await?;
+        }
+        None => {
+            stream.write_all(&resp_len.to_le_bytes()).await?;
+            stream.write_all(resp_bytes).await?;
+        }
+    }
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Reads the next frame like [`read_frame`], answering an oversized one with
+/// `413` and closing the connection, since the rest of the stream can no
+/// longer be split into frames.
+async fn next_frame<S>(
+    stream: &mut S,
+    codec: Codec,
+    options: &RuntimeOptions,
+) -> Result<Option<Vec<u8>>, ConnectionError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    match read_frame(stream, options.max_request_bytes()).await {
+        Err(ConnectionError::TooLarge { size, max }) => {
+            tracing::warn!(size, max, "request frame too large, closing connection");
+            let resp = ApiError::new(ErrorKind::PayloadTooLarge, "request too large")
+                .detail(serde_json::json!({ "size": size, "maxRequestBytes": max }))
+                .into_response(0);
+            write_response(stream, codec, &resp).await?;
+            stream.shutdown().await?;
+            Err(ConnectionError::TooLarge { size, max })
+        }
+        result => result,
+    }
+}
+
+/// Recovers the request id of a frame that failed to decode, `0` if none.
+///
+/// Tries the frame as a generic value first; for JSON that is not even
+/// valid UTF-8 it falls back to reading the digits after the opening `[`.
+fn best_effort_id(codec: Codec, buf: &[u8]) -> u8 {
+    if let Ok(value) = super::codec::decode::<serde_json::Value>(codec, buf) {
+        return value
+            .get(0)
+            .and_then(serde_json::Value::as_u64)
+            .and_then(|id| u8::try_from(id).ok())
+            .unwrap_or(0);
+    }
+    if codec != Codec::Json {
+        return 0;
+    }
+    let rest = buf.trim_ascii_start();
+    let Some(rest) = rest.strip_prefix(b"[") else {
+        return 0;
+    };
+    let rest = rest.trim_ascii_start();
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    std::str::from_utf8(&rest[..digits])
+        .ok()
+        .and_then(|id| id.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Turns away a client beyond `max_connections` with a `503` frame.
+///
+/// Sent before any handshake, so it is always JSON with id `0`.
+pub async fn refuse<S>(stream: &mut S, max_connections: usize) -> Result<(), ConnectionError>
+where
+    S: AsyncWrite + Unpin,
+{
+    tracing::warn!(max_connections, "connection limit reached, refusing client");
+    let resp = ApiError::new(ErrorKind::Unavailable, "too many connections")
+        .detail(serde_json::json!({ "maxConnections": max_connections }))
+        .into_response(0);
+    write_response(stream, Codec::Json, &resp).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Compares two secrets without short-circuiting on the first mismatch.
+fn token_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Reads the client hello of an [`AUTH_API`] or [`HANDSHAKE_API`] frame.
+///
+/// `system.auth` carries no protocol version and is taken to speak the
+/// current one.
+fn hello(req: &ApiRequest) -> Option<HandshakeRequest> {
+    if req.1 == HANDSHAKE_API {
+        return req.args().single::<HandshakeRequest>().ok();
+    }
+    let (token, codec, binary) = req
+        .args()
+        .optional::<(Option<String>, Option<Codec>, Option<bool>)>(3)
+        .ok()?;
+    Some(HandshakeRequest {
+        protocol: crate::api_manager::PROTOCOL_VERSION,
+        codec,
+        token,
+        binary,
+    })
+}
+
+/// Runs the `system.auth` or `system.handshake` handshake.
+///
+/// Every frame before a matching token is answered with `401`; after
+/// `max_auth_attempts` failures the connection is given up. A client asking
+/// for a different protocol or codec than the server uses is rejected with
+/// `400` right away. Returns whether the client accepts binary parts (see
+/// [`BINARY_FLAG`]), `None` if it hung up.
+async fn handshake<S>(
     stream: &mut S,
-    proxy: crate::utils::FrameEventLoopProxy,
+    options: &RuntimeOptions,
+) -> Result<Option<bool>, ConnectionError>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    for _ in 0..options.max_auth_attempts() {
+        let Some(buf) = next_frame(stream, Codec::Json, options).await? else {
+            return Ok(None);
+        };
+
+        let req = serde_json::from_slice::<ApiRequest>(&buf)
+            .ok()
+            .filter(|req| req.1 == AUTH_API || req.1 == HANDSHAKE_API);
+        let id = req.as_ref().map_or(0, |req| req.0);
+        let hello = req.as_ref().and_then(hello).unwrap_or_default();
+
+        let authorized = match &options.auth_token {
+            Some(token) => hello
+                .token
+                .as_deref()
+                .is_some_and(|presented| token_eq(presented, token)),
+            None => true,
+        };
+        if !authorized {
+            let resp = ApiError::new(ErrorKind::Unauthorized, "unauthorized").into_response(id);
+            write_response(stream, Codec::Json, &resp).await?;
+            continue;
+        }
+
+        let info = match HandshakeInfo::negotiate(&hello, options) {
+            Ok(info) => info,
+            Err(err) => {
+                write_response(stream, Codec::Json, &err.into_response(id)).await?;
+                return Err(ConnectionError::Auth("handshake rejected"));
+            }
+        };
+
+        let binary = info.binary;
+        let resp = match req {
+            Some(req) if req.1 == HANDSHAKE_API => req.ok(info),
+            _ => ApiResponse(
+                id,
+                0,
+                "ok".to_string(),
+                info.codec.name().into(),
+                None,
+                None,
+            ),
+        };
+        write_response(stream, Codec::Json, &resp).await?;
+        return Ok(Some(binary));
+    }
+
+    Err(ConnectionError::Auth("too many failed attempts"))
+}
+
+/// Hands one request to the event loop and waits for its response.
+#[tracing::instrument(name = "request", skip_all, fields(id = req.0, api = %req.1))]
+async fn dispatch<P: RequestSink>(
+    req: ApiRequest,
+    proxy: &P,
+    pending: &crate::utils::PendingMap,
+    options: &RuntimeOptions,
+) -> ApiResponse {
+    // === 4. Future registrieren ===
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let registered = {
+        let mut map = pending.lock().unwrap();
+        match map.entry(req.0) {
+            std::collections::hash_map::Entry::Occupied(_) => false,
+            std::collections::hash_map::Entry::Vacant(slot) => {
+                slot.insert(tx);
+                true
+            }
+        }
+    };
+
+    // === 5. Antwort abwarten ===
+    if !registered {
+        // Another in-flight request owns this id, possibly on another
+        // connection since the map is shared: never clobber its sender
+        return ApiError::new(ErrorKind::Conflict, "duplicate request id").into_response(req.0);
+    }
+
+    proxy.submit(req.clone());
+
+    match tokio::time::timeout(options.request_timeout(), rx).await {
+        Ok(Ok(resp)) => resp,
+        Ok(Err(_)) => {
+            pending.lock().unwrap().remove(&req.0);
+            ApiError::new(ErrorKind::Internal, "Internal server error").into_response(req.0)
+        }
+        Err(_) => {
+            // The event loop never answered: drop the stale sender
+            tracing::warn!(timeout = ?options.request_timeout(), "handler timed out");
+            pending.lock().unwrap().remove(&req.0);
+            ApiError::new(ErrorKind::Timeout, "handler timeout").into_response(req.0)
+        }
+    }
+}
+
+/// Runs the sub-requests of a [`BATCH_API`] frame one after another.
+async fn batch<P: RequestSink>(
+    req: &ApiRequest,
+    proxy: &P,
+    pending: &crate::utils::PendingMap,
+    options: &RuntimeOptions,
+) -> ApiResponse {
+    let (requests, stop_on_error) = match req.args().optional::<(Vec<ApiRequest>, Option<bool>)>(2)
+    {
+        Ok(args) => args,
+        Err(e) => return req.fail(ApiError::invalid_args(e)),
+    };
+
+    let mut responses = Vec::with_capacity(requests.len());
+    for sub in requests {
+        let resp = if sub.1 == BATCH_API || sub.1 == AUTH_API || sub.1 == HANDSHAKE_API {
+            ApiError::new(
+                ErrorKind::InvalidRequest,
+                format!("{} is not allowed in a batch", sub.1),
+            )
+            .into_response(sub.0)
+        } else {
+            dispatch(sub, proxy, pending, options).await
+        };
+        let failed = resp.1 != 0;
+        // A batch answers in one frame, leaving no room for binary parts
+        responses.push(resp.inline_binary());
+        if failed && stop_on_error.unwrap_or(false) {
+            break;
+        }
+    }
+    req.ok(responses)
+}
+
+pub async fn handle_client<S, P>(
+    stream: &mut S,
+    proxy: P,
     pending: crate::utils::PendingMap,
-) -> tokio::io::Result<()>
+    options: std::sync::Arc<RuntimeOptions>,
+) -> Result<(), ConnectionError>
 where
     S: AsyncRead + AsyncWrite + Unpin,
+    P: RequestSink,
 {
-    loop {
-        // === 1. Länge lesen ===
-        let mut len_buf = [0u8; 4];
-        if let Err(_) | Ok(0) = stream.read_exact(&mut len_buf).await {
-            return Ok(()); // Verbindung beendet
+    let codec = options.codec();
+    let mut binary = if options.auth_token.is_some() || codec != Codec::Json {
+        match handshake(stream, &options).await? {
+            Some(binary) => binary,
+            None => return Ok(()),
         }
-        let len = u32::from_le_bytes(len_buf) as usize;
-
-        // === 2. Nachricht lesen ===
-        let mut buf = vec![0u8; len];
-        stream.read_exact(&mut buf).await?;
-        let request_str = match String::from_utf8(buf) {
-            Ok(s) => s,
-            Err(_) => continue,
+    } else {
+        false
+    };
+
+    loop {
+        let Some(buf) = next_frame(stream, codec, &options).await? else {
+            return Ok(());
         };
+        crate::metrics::METRICS.request_received();
 
-        // === 3. JSON in ApiRequest parsen ===
-        let req: crate::api_manager::ApiRequest = match serde_json::from_str(&request_str) {
+        // === 3. Frame in ApiRequest dekodieren ===
+        let req: ApiRequest = match super::codec::decode(codec, &buf) {
             Ok(req) => req,
             Err(e) => {
-                eprintln!("[platform] JSON parse error: {:?}", e);
+                // Frames are length-prefixed, so the next one is still intact
+                let err = ConnectionError::Decode(e);
+                tracing::warn!(codec = codec.name(), kind = err.kind(), error = %err, "malformed request");
+                let resp = ApiError::new(ErrorKind::InvalidRequest, "malformed request")
+                    .detail(serde_json::json!({ "error": err.to_string() }))
+                    .into_response(best_effort_id(codec, &buf));
+                write_response(stream, codec, &resp).await?;
+                crate::metrics::METRICS.response_sent(resp.1);
                 continue;
             }
         };
 
-        // === 4. Future registrieren ===
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        {
-            let mut map = pending.lock().unwrap();
-            map.insert(req.0.clone(), tx);
+        let resp = if req.1 == BATCH_API {
+            batch(&req, &proxy, &pending, &options).await
+        } else if req.1 == HANDSHAKE_API {
+            // Optional without a token: negotiates binary parts and reports
+            // the server capabilities, or ends the connection on a mismatch
+            match req.args().single::<HandshakeRequest>() {
+                Err(e) => req.fail(ApiError::invalid_args(e)),
+                Ok(hello) => match HandshakeInfo::negotiate(&hello, &options) {
+                    Ok(info) => {
+                        binary = info.binary;
+                        req.ok(info)
+                    }
+                    Err(err) => {
+                        write_response(stream, codec, &err.into_response(req.0)).await?;
+                        return Err(ConnectionError::Auth("handshake rejected"));
+                    }
+                },
+            }
+        } else {
+            dispatch(req, &proxy, &pending, &options).await
+        };
+        let resp = if binary { resp } else { resp.inline_binary() };
+
+        write_response(stream, codec, &resp).await?;
+        crate::metrics::METRICS.response_sent(resp.1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_manager::ApiManager;
+    use crate::utils::PendingMap;
+    use std::future::Future;
+    use std::sync::Arc;
+    use tokio::io::DuplexStream;
+
+    type Answer = Box<dyn Fn(&ApiRequest) -> Option<ApiResponse>>;
+
+    /// Stands in for the event loop: answers each request on the spot
+    /// through the pending map, the way [`crate::core::App::respond`] does,
+    /// or leaves it unanswered when `answer` returns `None`.
+    struct Loopback {
+        pending: PendingMap,
+        answer: Answer,
+    }
+
+    impl Loopback {
+        fn new(answer: impl Fn(&ApiRequest) -> Option<ApiResponse> + 'static) -> Self {
+            Self {
+                pending: Default::default(),
+                answer: Box::new(answer),
+            }
         }
+    }
 
-        let _ = proxy.send_event(crate::utils::UserEvent::Request(req.clone()));
+    impl RequestSink for Loopback {
+        fn submit(&self, req: ApiRequest) {
+            if let Some(resp) = (self.answer)(&req) {
+                if let Some(sender) = self.pending.lock().unwrap().remove(&req.0) {
+                    let _ = sender.send(resp);
+                }
+            }
+        }
+    }
 
-        // === 5. Antwort senden ===
-        let resp = match rx.await {
-            Ok(resp) => resp,
-            Err(_) => crate::api_manager::ApiResponse(
-                req.0,
-                500,
-                "Internal server error".to_string(),
-                serde_json::json!(null),
-            ),
+    /// Serves one connection over an in-memory stream while `client` talks
+    /// to the other end; returns how the server finished and what the client
+    /// returned. Dropping the client end hangs up, and the client sees the
+    /// server hang up once `handle_client` returns.
+    async fn serve<F, T>(
+        options: RuntimeOptions,
+        sink: Loopback,
+        client: impl FnOnce(DuplexStream) -> F,
+    ) -> (Result<(), ConnectionError>, T)
+    where
+        F: Future<Output = T>,
+    {
+        let (mut server, peer) = tokio::io::duplex(1 << 20);
+        let pending = sink.pending.clone();
+        // The server end goes away with the connection, as a socket would
+        let server =
+            async move { handle_client(&mut server, sink, pending, Arc::new(options)).await };
+        tokio::join!(server, client(peer))
+    }
+
+    async fn send(stream: &mut DuplexStream, frame: &[u8]) {
+        stream
+            .write_all(&(frame.len() as u32).to_le_bytes())
+            .await
+            .unwrap();
+        stream.write_all(frame).await.unwrap();
+    }
+
+    /// Reads one JSON response frame, `None` once the server hung up.
+    async fn recv(stream: &mut DuplexStream) -> Option<serde_json::Value> {
+        let mut header = [0u8; 4];
+        stream.read_exact(&mut header).await.ok()?;
+        let mut body = vec![0u8; (u32::from_le_bytes(header) & !BINARY_FLAG) as usize];
+        stream.read_exact(&mut body).await.ok()?;
+        Some(serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn answers_through_the_registered_apis() {
+        let manager = ApiManager::new();
+        crate::api::register_api_instances(&mut manager.lock().unwrap());
+        let sink = Loopback::new(move |req| manager.lock().unwrap().answer_unhandled(req));
+
+        let (result, listed) = serve(RuntimeOptions::default(), sink, |mut client| async move {
+            send(&mut client, br#"[1, "system.listApis", []]"#).await;
+            recv(&mut client).await
+        })
+        .await;
+
+        result.unwrap();
+        let listed = listed.unwrap();
+        assert_eq!(listed[0], 1);
+        assert_eq!(listed[1], 0);
+        let names = listed[3].as_array().unwrap();
+        assert!(names.iter().any(|name| name == "window.set_title"));
+        assert!(names.iter().any(|name| name == "webview.openDevtools"));
+    }
+
+    #[tokio::test]
+    async fn unanswered_request_times_out() {
+        let sink = Loopback::new(|_| None);
+        let pending = sink.pending.clone();
+        let options = RuntimeOptions {
+            request_timeout_ms: Some(20),
+            ..Default::default()
+        };
+
+        let (result, resp) = serve(options, sink, |mut client| async move {
+            send(&mut client, br#"[7, "window.set_title", ["x"]]"#).await;
+            recv(&mut client).await
+        })
+        .await;
+
+        result.unwrap();
+        let resp = resp.unwrap();
+        assert_eq!(resp[0], 7);
+        assert_eq!(resp[1], 504);
+        assert_eq!(resp[3]["kind"], "timeout");
+        // The stale sender must not block the id for the next request
+        assert!(pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn same_id_from_two_clients_conflicts() {
+        let pending = PendingMap::default();
+        let options = Arc::new(RuntimeOptions {
+            request_timeout_ms: Some(50),
+            ..Default::default()
+        });
+        let submitted = Arc::new(tokio::sync::Notify::new());
+        // Client A's request stays in flight until it times out
+        let sink_a = Loopback {
+            pending: pending.clone(),
+            answer: Box::new({
+                let submitted = submitted.clone();
+                move |_| {
+                    submitted.notify_one();
+                    None
+                }
+            }),
+        };
+        let sink_b = Loopback {
+            pending: pending.clone(),
+            answer: Box::new(|req| Some(req.ok(true))),
         };
+        let (mut server_a, mut client_a) = tokio::io::duplex(1 << 16);
+        let (mut server_b, mut client_b) = tokio::io::duplex(1 << 16);
 
-        let response_json = serde_json::to_string(&resp)?;
-        let resp_bytes = response_json.as_bytes();
-        let resp_len = resp_bytes.len() as u32;
+        let clients = async move {
+            send(&mut client_a, br#"[5, "window.set_title", ["a"]]"#).await;
+            submitted.notified().await;
+            send(&mut client_b, br#"[5, "window.set_title", ["b"]]"#).await;
+            let conflict = recv(&mut client_b).await.unwrap();
+            let timeout = recv(&mut client_a).await.unwrap();
+            // Once A's request is gone the id is free again
+            send(&mut client_b, br#"[5, "window.set_title", ["b"]]"#).await;
+            let retry = recv(&mut client_b).await.unwrap();
+            (conflict, timeout, retry)
+        };
+        let (result_a, result_b, (conflict, timeout, retry)) = tokio::join!(
+            handle_client(&mut server_a, sink_a, pending.clone(), options.clone()),
+            handle_client(&mut server_b, sink_b, pending.clone(), options.clone()),
+            clients,
+        );
+
+        result_a.unwrap();
+        result_b.unwrap();
+        assert_eq!(conflict[0], 5);
+        assert_eq!(conflict[1], 409);
+        assert_eq!(timeout[1], 504);
+        assert_eq!(retry[1], 0);
+    }
+
+    fn with_token(max_auth_attempts: u32) -> RuntimeOptions {
+        RuntimeOptions {
+            auth_token: Some("s3cret".to_string()),
+            max_auth_attempts: Some(max_auth_attempts),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn handshake_accepts_the_token() {
+        let sink = Loopback::new(|req| Some(req.ok(true)));
+        let (result, (auth, resp)) = serve(with_token(3), sink, |mut client| async move {
+            send(&mut client, br#"[0, "system.auth", ["s3cret"]]"#).await;
+            let auth = recv(&mut client).await.unwrap();
+            send(&mut client, br#"[1, "window.set_title", ["x"]]"#).await;
+            (auth, recv(&mut client).await.unwrap())
+        })
+        .await;
+
+        result.unwrap();
+        assert_eq!(auth[1], 0);
+        assert_eq!(auth[3], "json");
+        assert_eq!(resp[0], 1);
+        assert_eq!(resp[1], 0);
+    }
+
+    #[tokio::test]
+    async fn handshake_rejects_a_wrong_token() {
+        let sink = Loopback::new(|req| Some(req.ok(true)));
+        let (result, (denied, auth)) = serve(with_token(3), sink, |mut client| async move {
+            send(&mut client, br#"[0, "system.auth", ["guess"]]"#).await;
+            let denied = recv(&mut client).await.unwrap();
+            send(&mut client, br#"[0, "system.auth", ["s3cret"]]"#).await;
+            (denied, recv(&mut client).await.unwrap())
+        })
+        .await;
+
+        result.unwrap();
+        assert_eq!(denied[1], 401);
+        assert_eq!(denied[3]["kind"], "unauthorized");
+        assert_eq!(auth[1], 0);
+    }
+
+    #[tokio::test]
+    async fn handshake_gives_up_after_max_auth_attempts() {
+        let sink = Loopback::new(|req| Some(req.ok(true)));
+        let (result, answers) = serve(with_token(2), sink, |mut client| async move {
+            let mut answers = Vec::new();
+            for _ in 0..2 {
+                send(&mut client, br#"[0, "system.auth", ["guess"]]"#).await;
+                answers.push(recv(&mut client).await);
+            }
+            // No third attempt: the server hangs up
+            answers.push(recv(&mut client).await);
+            answers
+        })
+        .await;
+
+        assert!(matches!(result, Err(ConnectionError::Auth(_))));
+        assert_eq!(answers[0].as_ref().unwrap()[1], 401);
+        assert_eq!(answers[1].as_ref().unwrap()[1], 401);
+        assert!(answers[2].is_none());
+    }
+
+    #[tokio::test]
+    async fn malformed_frame_leaves_the_stream_usable() {
+        let sink = Loopback::new(|req| Some(req.ok(true)));
+        let (result, (malformed, resp)) =
+            serve(RuntimeOptions::default(), sink, |mut client| async move {
+                send(&mut client, b"[3, \"window.set_title\", [\"\xff\xfe\"]]").await;
+                let malformed = recv(&mut client).await.unwrap();
+                send(&mut client, br#"[4, "window.set_title", ["x"]]"#).await;
+                (malformed, recv(&mut client).await.unwrap())
+            })
+            .await;
+
+        result.unwrap();
+        assert_eq!(malformed[0], 3);
+        assert_eq!(malformed[1], 400);
+        assert_eq!(malformed[3]["kind"], "invalidRequest");
+        assert_eq!(resp[0], 4);
+        assert_eq!(resp[1], 0);
+    }
+
+    #[tokio::test]
+    async fn oversized_frame_is_refused_before_its_body() {
+        let sink = Loopback::new(|req| Some(req.ok(true)));
+        let options = RuntimeOptions {
+            max_request_bytes: Some(64),
+            ..Default::default()
+        };
+        let (result, (refused, after)) = serve(options, sink, |mut client| async move {
+            // Only the header: a server waiting for the claimed body, let
+            // alone allocating it, would never answer
+            client.write_all(&65u32.to_le_bytes()).await.unwrap();
+            let refused = recv(&mut client).await.unwrap();
+            (refused, recv(&mut client).await)
+        })
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(ConnectionError::TooLarge { size: 65, max: 64 })
+        ));
+        assert_eq!(refused[1], 413);
+        assert_eq!(refused[3]["detail"]["size"], 65);
+        assert_eq!(refused[3]["detail"]["maxRequestBytes"], 64);
+        assert!(after.is_none());
+    }
+
+    #[tokio::test]
+    async fn unknown_api_is_answered_with_its_name() {
+        let manager = ApiManager::new();
+        crate::api::register_api_instances(&mut manager.lock().unwrap());
+        let sink = Loopback::new(move |req| manager.lock().unwrap().answer_unhandled(req));
+
+        let (result, resp) = serve(RuntimeOptions::default(), sink, |mut client| async move {
+            send(&mut client, br#"[2, "window.setTitel", ["x"]]"#).await;
+            recv(&mut client).await
+        })
+        .await;
+
+        result.unwrap();
+        let resp = resp.unwrap();
+        assert_eq!(resp[0], 2);
+        assert_eq!(resp[1], 404);
+        assert_eq!(resp[2], "unknown api: window.setTitel");
+        assert_eq!(resp[3]["detail"]["api"], "window.setTitel");
+    }
+
+    #[tokio::test]
+    async fn binary_part_beats_base64_for_a_five_megabyte_file() {
+        let req: ApiRequest = serde_json::from_str(r#"[1, "fs.readFile", []]"#).unwrap();
+        let file: Vec<u8> = (0..5 << 20).map(|i| (i % 251) as u8).collect();
+        let resp = req.binary(file.clone());
+
+        let mut sizes = Vec::new();
+        for resp in [resp.clone(), resp.inline_binary()] {
+            let mut out = Vec::new();
+            write_response(&mut out, Codec::Json, &resp).await.unwrap();
+            sizes.push(out.len());
+        }
 
-        stream.write_all(&resp_len.to_le_bytes()).await?;
-        stream.write_all(resp_bytes).await?;
-        stream.flush().await?;
+        let (binary, base64) = (sizes[0], sizes[1]);
+        // Header, a short JSON frame and the length of the binary part
+        assert!(binary < file.len() + 64, "{binary}");
+        assert!(base64 >= file.len() * 4 / 3, "{base64}");
     }
 }
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+pub mod codec;
+pub mod error;
 pub mod handler;
 pub mod unix_conn;
 pub mod utils;
@@ -44,99 +46,409 @@ pub mod utils;
 /// [`windows_conn::platform_main`]: crate::windows_conn::platform_main
 /// [`unix_conn::platform_main`]: crate::unix_conn::platform_main
 pub mod windows_conn;
+use once_cell::sync::Lazy;
 use pyo3::prelude::*;
+use std::collections::HashMap;
+
+/// Lifecycle of the connection listener, reported as `connectionStateChanged`.
+#[derive(serde::Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionState {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+/// Emits a `connectionStateChanged` event to the frontend.
+pub fn notify_state(
+    proxy: &crate::utils::FrameEventLoopProxy,
+    state: ConnectionState,
+    detail: serde_json::Value,
+) {
+    let mut payload = serde_json::json!({ "state": state, "source": "platform" });
+    if let (Some(payload), serde_json::Value::Object(detail)) = (payload.as_object_mut(), detail) {
+        payload.extend(detail);
+    }
+    let _ = proxy.send_event(crate::utils::UserEvent::Emit(
+        "connectionStateChanged".to_string(),
+        payload,
+    ));
+}
 
 #[allow(dead_code)]
 pub async fn start_connection(
     proxy: crate::utils::FrameEventLoopProxy,
     pending: super::utils::PendingMap,
     name: String,
+    options: std::sync::Arc<pyorion_options::runtime::RuntimeOptions>,
 ) -> std::io::Result<()> {
-    #[cfg(windows)]
-    {
-        return windows_conn::platform_main(proxy, pending, &name).await;
+    let backoff = options.reconnect();
+    let attempt = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+
+    loop {
+        // Reset the retry budget as soon as the listener is up again
+        let on_listening = {
+            let proxy = proxy.clone();
+            let attempt = attempt.clone();
+            move || {
+                attempt.store(0, std::sync::atomic::Ordering::Relaxed);
+                notify_state(&proxy, ConnectionState::Connected, serde_json::json!({}));
+                let _ = proxy.send_event(crate::utils::UserEvent::Milestone(
+                    crate::utils::Milestone::Listening,
+                ));
+            }
+        };
+
+        #[cfg(windows)]
+        let result = windows_conn::platform_main(
+            proxy.clone(),
+            pending.clone(),
+            &name,
+            options.clone(),
+            on_listening,
+        )
+        .await;
+
+        #[cfg(unix)]
+        let result = unix_conn::platform_main(
+            proxy.clone(),
+            pending.clone(),
+            &name,
+            options.clone(),
+            on_listening,
+        )
+        .await;
+
+        let err = match result {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+
+        let attempt = attempt.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        if attempt > backoff.max_retries() {
+            notify_state(
+                &proxy,
+                ConnectionState::Disconnected,
+                serde_json::json!({ "error": err.to_string() }),
+            );
+            return Err(err);
+        }
+
+        let delay = backoff.delay(attempt);
+        tracing::warn!(error = %err, attempt, ?delay, "listener failed, retrying");
+        notify_state(
+            &proxy,
+            ConnectionState::Reconnecting,
+            serde_json::json!({
+                "attempt": attempt,
+                "delayMs": delay.as_millis() as u64,
+                "error": err.to_string(),
+            }),
+        );
+        tokio::time::sleep(delay).await;
     }
+}
 
-    #[cfg(unix)]
-    {
-        return unix_conn::platform_main(proxy, pending, &name).await;
+/// Writes `message` as one length-prefixed frame and reads the reply frame,
+/// along with its binary part if the reply carries one.
+async fn exchange<S>(stream: &mut S, message: &[u8]) -> anyhow::Result<(Vec<u8>, Option<Vec<u8>>)>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Nachricht mit Länge schicken
+    let len = message.len() as u32;
+    stream.write_all(&len.to_le_bytes()).await?;
+    stream.write_all(message).await?;
+    stream.flush().await?;
+
+    // Antwort lesen
+    let mut len_buf = [0u8; 4];
+    stream.read_exact(&mut len_buf).await?;
+    let header = u32::from_le_bytes(len_buf);
+    let resp_len = (header & !handler::BINARY_FLAG) as usize;
+
+    let mut resp_buf = vec![0u8; resp_len];
+    stream.read_exact(&mut resp_buf).await?;
+
+    if header & handler::BINARY_FLAG == 0 {
+        return Ok((resp_buf, None));
     }
+    stream.read_exact(&mut len_buf).await?;
+    let mut binary = vec![0u8; u32::from_le_bytes(len_buf) as usize];
+    stream.read_exact(&mut binary).await?;
+    Ok((resp_buf, Some(binary)))
 }
 
-#[pyo3::pyfunction]
-pub fn send_event_over_platform<'py>(
-    py: Python<'py>,
-    name: String,
-    message: String,
-) -> PyResult<Bound<'py, PyAny>> {
+/// Presents `token` and `codec` via `system.handshake` before any request is
+/// sent, failing fast if the server speaks another protocol version.
+async fn handshake<S>(
+    stream: &mut S,
+    token: Option<&str>,
+    codec: pyorion_options::runtime::Codec,
+) -> anyhow::Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let hello = crate::api_manager::HandshakeRequest {
+        protocol: crate::api_manager::PROTOCOL_VERSION,
+        codec: Some(codec),
+        token: token.map(str::to_string),
+        binary: Some(true),
+    };
+    let frame = serde_json::json!([0, handler::HANDSHAKE_API, [hello]]).to_string();
+    let resp: serde_json::Value =
+        serde_json::from_slice(&exchange(stream, frame.as_bytes()).await?.0)?;
+    match resp.get(1).and_then(serde_json::Value::as_i64) {
+        Some(0) => Ok(()),
+        _ => Err(anyhow::anyhow!(
+            "handshake rejected: {}",
+            resp.get(2)
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or("unknown error")
+        )),
+    }
+}
+
+#[cfg(windows)]
+type ClientStream = tokio::net::windows::named_pipe::NamedPipeClient;
+#[cfg(unix)]
+type ClientStream = tokio::net::UnixStream;
+
+/// Where a client connection leads and what its handshake negotiated.
+type ClientKey = (String, Option<String>, pyorion_options::runtime::Codec);
+type SharedClient = std::sync::Arc<tokio::sync::Mutex<Option<ClientStream>>>;
+
+/// One open connection per listener, reused by every call of this process.
+///
+/// Opening a connection per call would count each concurrent call against
+/// `maxConnections` and have a large `asyncio.gather` refused with `503`;
+/// instead calls take turns on the shared one, as the Python dispatcher
+/// sends them anyway.
+static CLIENTS: Lazy<std::sync::Mutex<HashMap<ClientKey, SharedClient>>> =
+    Lazy::new(Default::default);
+
+/// Opens a client connection to the listener bound for `name`.
+async fn open(name: &str) -> anyhow::Result<ClientStream> {
     #[cfg(windows)]
-    let fut = async move {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    {
         use tokio::net::windows::named_pipe::ClientOptions;
         use tokio::time::{sleep, Duration};
         use windows_sys::Win32::Foundation::ERROR_PIPE_BUSY;
 
         let pipe_full_name = format!(r"\\.\pipe\{}", name);
 
-        let mut client = loop {
+        loop {
             match ClientOptions::new().open(&pipe_full_name) {
-                Ok(c) => break c,
+                Ok(c) => return Ok(c),
                 Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY as i32) => {}
                 Err(e) => return Err(anyhow::Error::from(e)),
             }
             sleep(Duration::from_millis(10)).await;
-        };
+        }
+    }
 
-        // Nachricht mit Länge schicken
-        let msg_bytes = message.as_bytes();
-        let len = msg_bytes.len() as u32;
-        client.write_all(&len.to_le_bytes()).await?;
-        client.write_all(msg_bytes).await?;
-        client.flush().await?;
+    #[cfg(unix)]
+    Ok(unix_conn::connect(name).await?)
+}
 
-        // Antwort lesen
-        let mut len_buf = [0u8; 4];
-        client.read_exact(&mut len_buf).await?;
-        let resp_len = u32::from_le_bytes(len_buf) as usize;
+/// Sends one JSON request to the listener bound for `name` over the shared
+/// connection, opening and handshaking it first if there is none.
+///
+/// A connection that failed mid-request is dropped, so the next call
+/// starts over on a fresh one. Binary parts are only negotiated by the
+/// handshake; without one, binary results arrive base64 encoded inside the
+/// JSON reply.
+async fn call(
+    name: String,
+    message: String,
+    token: Option<String>,
+    codec: pyorion_options::runtime::Codec,
+) -> anyhow::Result<(String, Option<Vec<u8>>)> {
+    let frame = codec::from_json(codec, &message)?;
+    let client = CLIENTS
+        .lock()
+        .unwrap()
+        .entry((name.clone(), token.clone(), codec))
+        .or_default()
+        .clone();
+    let mut slot = client.lock().await;
 
-        let mut resp_buf = vec![0u8; resp_len];
-        client.read_exact(&mut resp_buf).await?;
-        let resp_str = String::from_utf8_lossy(&resp_buf).to_string();
+    let stream = match slot.as_mut() {
+        Some(stream) => stream,
+        None => {
+            let mut stream = open(&name).await?;
+            if token.is_some() || codec != pyorion_options::runtime::Codec::Json {
+                handshake(&mut stream, token.as_deref(), codec).await?;
+            }
+            slot.insert(stream)
+        }
+    };
 
-        Ok::<String, anyhow::Error>(resp_str)
+    let (resp, binary) = match exchange(stream, &frame).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            *slot = None;
+            return Err(e);
+        }
     };
+    Ok((codec::to_json(codec, &resp)?, binary))
+}
 
-    #[cfg(unix)]
-    let fut = async move {
-        use tokio::io::{AsyncReadExt, AsyncWriteExt};
-        use tokio::net::UnixStream;
-
-        let path = format!("/tmp/{}", name);
-        let mut stream = UnixStream::connect(&path).await?;
-
-        // Nachricht mit Länge schicken
-        let msg_bytes = message.as_bytes();
-        let len = msg_bytes.len() as u32;
-        stream.write_all(&len.to_le_bytes()).await?;
-        stream.write_all(msg_bytes).await?;
-        stream.flush().await?;
-
-        // Antwort lesen
-        let mut len_buf = [0u8; 4];
-        stream.read_exact(&mut len_buf).await?;
-        let resp_len = u32::from_le_bytes(len_buf) as usize;
-
-        let mut resp_buf = vec![0u8; resp_len];
-        stream.read_exact(&mut resp_buf).await?;
-        let resp_str = String::from_utf8_lossy(&resp_buf).to_string();
-
-        Ok::<String, anyhow::Error>(resp_str)
+#[pyo3::pyfunction]
+#[pyo3(signature = (name, message, token=None, codec=None))]
+pub fn send_event_over_platform<'py>(
+    py: Python<'py>,
+    name: String,
+    message: String,
+    token: Option<String>,
+    codec: Option<String>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let codec: pyorion_options::runtime::Codec = match codec {
+        Some(codec) => codec
+            .parse()
+            .map_err(pyo3::exceptions::PyValueError::new_err)?,
+        None => Default::default(),
     };
 
+    let fut = call(name, message, token, codec);
+
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
         match fut.await {
-            Ok(resp) => Python::with_gil(|py| utils::json_to_py(py, &resp)),
+            Ok((resp, binary)) => Python::with_gil(|py| {
+                let resp = utils::json_to_py(py, &resp)?;
+                if let Some(binary) = binary {
+                    // The result slot holds the byte count: hand over the bytes
+                    resp.bind(py)
+                        .set_item(3, pyo3::types::PyBytes::new(py, &binary))?;
+                }
+                Ok(resp)
+            }),
             Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
         }
     })
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::api_manager::ApiRequest;
+    use crate::utils::PendingMap;
+    use pyorion_options::runtime::{Codec, RuntimeFlavor, RuntimeOptions};
+    use std::sync::Arc;
+    use tokio::io::AsyncReadExt;
+
+    /// Stands in for the event loop: answers every request with `ok` on the spot.
+    #[derive(Clone)]
+    struct Echo(PendingMap);
+
+    impl handler::RequestSink for Echo {
+        fn submit(&self, req: ApiRequest) {
+            if let Some(sender) = self.0.lock().unwrap().remove(&req.0) {
+                let _ = sender.send(req.ok(&req.1));
+            }
+        }
+    }
+
+    /// Listens on a fresh name with `max_connections` slots until the
+    /// returned task is aborted.
+    async fn listen(max_connections: usize) -> (String, tokio::task::JoinHandle<()>) {
+        let name = format!("pyorion-test-{}", uuid::Uuid::new_v4());
+        let options = Arc::new(RuntimeOptions {
+            max_connections: Some(max_connections),
+            ..Default::default()
+        });
+        let sink = Echo(PendingMap::default());
+        let (listening, ready) = tokio::sync::oneshot::channel();
+        let server = {
+            let name = name.clone();
+            tokio::spawn(async move {
+                let pending = sink.0.clone();
+                let on_listening = move || {
+                    let _ = listening.send(());
+                };
+                unix_conn::platform_main(sink, pending, &name, options, on_listening)
+                    .await
+                    .unwrap();
+            })
+        };
+        ready.await.unwrap();
+        (name, server)
+    }
+
+    #[tokio::test]
+    async fn connection_beyond_the_limit_is_refused() {
+        let (name, server) = listen(2).await;
+        let mut first = unix_conn::connect(&name).await.unwrap();
+        let _second = unix_conn::connect(&name).await.unwrap();
+        let mut third = unix_conn::connect(&name).await.unwrap();
+
+        let mut header = [0u8; 4];
+        third.read_exact(&mut header).await.unwrap();
+        let mut body = vec![0u8; u32::from_le_bytes(header) as usize];
+        third.read_exact(&mut body).await.unwrap();
+        let refused: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(refused[1], 503);
+        assert_eq!(refused[3]["detail"]["maxConnections"], 2);
+
+        // The connections within the limit are still served
+        let (resp, _) = exchange(&mut first, br#"[1, "app.version", []]"#)
+            .await
+            .unwrap();
+        let resp: serde_json::Value = serde_json::from_slice(&resp).unwrap();
+        assert_eq!(resp[1], 0);
+
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn concurrent_calls_share_one_connection() {
+        const MAX_CONNECTIONS: usize = 4;
+        let (name, server) = listen(MAX_CONNECTIONS).await;
+
+        let mut calls = tokio::task::JoinSet::new();
+        for id in 0..=MAX_CONNECTIONS {
+            let message = serde_json::json!([id, "app.version", []]).to_string();
+            calls.spawn(call(name.clone(), message, None, Codec::Json));
+        }
+        while let Some(result) = calls.join_next().await {
+            let (resp, _) = result.unwrap().unwrap();
+            let resp: serde_json::Value = serde_json::from_str(&resp).unwrap();
+            assert_eq!(resp[1], 0, "{resp}");
+        }
+
+        server.abort();
+    }
+
+    #[test]
+    fn connections_are_served_on_both_runtime_flavors() {
+        // The client gets its own runtime, so the server only makes progress
+        // through the runtime under test (and its driver thread)
+        let client = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        for flavor in [RuntimeFlavor::MultiThread, RuntimeFlavor::CurrentThread] {
+            let options = RuntimeOptions {
+                runtime_flavor: Some(flavor),
+                ..Default::default()
+            };
+            let (rt, driver) = crate::core::build_runtime(&options).unwrap();
+            assert_eq!(driver.is_some(), flavor == RuntimeFlavor::CurrentThread);
+
+            let (name, server) = client.block_on(rt.spawn(listen(1))).unwrap();
+            let message = r#"[1, "app.version", []]"#.to_string();
+            let (resp, _) = client
+                .block_on(call(name, message, None, Codec::Json))
+                .unwrap();
+            let resp: serde_json::Value = serde_json::from_str(&resp).unwrap();
+            assert_eq!(resp[1], 0, "{flavor:?}: {resp}");
+
+            server.abort();
+            if let Some(driver) = driver {
+                driver.stop();
+            }
+        }
+    }
+}
@@ -3,44 +3,145 @@
 // SPDX-License-Identifier: MIT
 
 #[cfg(unix)]
-pub async fn platform_main(
-    proxy: crate::utils::FrameEventLoopProxy,
-    pending: crate::utils::PendingMap,
-    pipe_name: &str,
-) -> std::io::Result<()> {
+use std::path::PathBuf;
+
+/// Resolves the socket file path for `name` inside the platform temp dir.
+#[cfg(unix)]
+pub fn socket_path(name: &str) -> PathBuf {
+    let mut path: PathBuf = std::env::temp_dir();
+    path.push(name);
+    path
+}
+
+/// Unlinks the socket file once the listener is dropped.
+#[cfg(unix)]
+struct SocketFileGuard(PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFileGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Binds the listener for `name`.
+///
+/// On Linux a name starting with `@` is bound in the abstract namespace and
+/// never touches the filesystem. Otherwise the socket file is created in the
+/// temp dir with `mode` (owner-only by default) applied from the start.
+#[cfg(unix)]
+fn bind(
+    name: &str,
+    mode: u32,
+) -> std::io::Result<(tokio::net::UnixListener, Option<SocketFileGuard>)> {
     use std::fs;
-    use std::os::unix::fs::PermissionsExt;
-    use std::path::PathBuf;
     use tokio::net::UnixListener;
 
-    // Platform Secure Temp Path
-    let mut path: PathBuf = std::env::temp_dir();
-    path.push(pipe_name);
+    #[cfg(target_os = "linux")]
+    if let Some(abstract_name) = name.strip_prefix('@') {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(abstract_name)?;
+        let listener = std::os::unix::net::UnixListener::bind_addr(&addr)?;
+        listener.set_nonblocking(true)?;
+        return Ok((UnixListener::from_std(listener)?, None));
+    }
+
+    let path = socket_path(name);
 
     // Remove old file if necessary
     let _ = fs::remove_file(&path);
 
-    // Bind listeners
-    let listener = UnixListener::bind(&path)?;
+    // Secure socket file: bind under a umask that leaves exactly `mode`, as
+    // a chmod after bind would let anyone connect in between
+    let listener = {
+        let previous = unsafe { libc::umask((!mode & 0o777) as libc::mode_t) };
+        let listener = UnixListener::bind(&path);
+        unsafe { libc::umask(previous) };
+        listener?
+    };
+    let guard = SocketFileGuard(path);
 
-    // Secure socket file: only owner can read/write
-    let mut perms = fs::metadata(&path)?.permissions();
-    perms.set_mode(0o600);
-    fs::set_permissions(&path, perms)?;
+    Ok((listener, Some(guard)))
+}
+
+/// Connects to the listener bound by [`platform_main`] for `name`.
+#[cfg(unix)]
+pub async fn connect(name: &str) -> std::io::Result<tokio::net::UnixStream> {
+    #[cfg(target_os = "linux")]
+    if let Some(abstract_name) = name.strip_prefix('@') {
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(abstract_name)?;
+        let stream = std::os::unix::net::UnixStream::connect_addr(&addr)?;
+        stream.set_nonblocking(true)?;
+        return tokio::net::UnixStream::from_std(stream);
+    }
 
-    // println!("Secure UDS Server runs on{}", path.display());
+    tokio::net::UnixStream::connect(socket_path(name)).await
+}
+
+#[cfg(unix)]
+pub async fn platform_main<P>(
+    proxy: P,
+    pending: crate::utils::PendingMap,
+    pipe_name: &str,
+    options: std::sync::Arc<pyorion_options::runtime::RuntimeOptions>,
+    on_listening: impl FnOnce(),
+) -> std::io::Result<()>
+where
+    P: crate::connections::handler::RequestSink + Clone + Send + Sync + 'static,
+{
+    // The guard keeps the socket file alive for as long as we listen
+    let (listener, _guard) = bind(pipe_name, options.socket_mode())?;
+    on_listening();
+    tracing::debug!(name = pipe_name, "listening on unix socket");
+
+    // Client tasks live in the set so dropping the listener aborts them too
+    let mut clients = tokio::task::JoinSet::new();
 
     loop {
         let (mut stream, _) = listener.accept().await?;
         let proxy = proxy.clone();
         let pending = pending.clone();
+        let options = options.clone();
+
+        // Reap finished clients; the rest are aborted with the set
+        while clients.try_join_next().is_some() {}
+
+        if clients.len() >= options.max_connections() {
+            let max_connections = options.max_connections();
+            let _ = crate::connections::handler::refuse(&mut stream, max_connections).await;
+            continue;
+        }
 
-        tokio::spawn(async move {
+        clients.spawn(async move {
             if let Err(e) =
-                crate::connections::handler::handle_client(&mut stream, proxy, pending).await
+                crate::connections::handler::handle_client(&mut stream, proxy, pending, options)
+                    .await
             {
-                eprintln!("[UDS] Client error: {:?}", e);
+                tracing::warn!(kind = e.kind(), error = %e, "UDS client error");
             }
         });
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[tokio::test]
+    async fn socket_file_is_created_with_mode() {
+        for mode in [0o600, 0o660] {
+            let name = format!("pyorion-test-{}", uuid::Uuid::new_v4());
+            let (_listener, guard) = bind(&name, mode).unwrap();
+            let path = socket_path(&name);
+            let meta = std::fs::metadata(&path).unwrap();
+            assert_eq!(meta.permissions().mode() & 0o777, mode);
+
+            drop(guard);
+            assert!(!path.exists());
+        }
+    }
+}
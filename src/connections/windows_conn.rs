@@ -3,11 +3,16 @@
 // SPDX-License-Identifier: MIT
 
 #[cfg(windows)]
-pub async fn platform_main(
-    proxy: crate::utils::FrameEventLoopProxy,
+pub async fn platform_main<P>(
+    proxy: P,
     pending: crate::utils::PendingMap,
     pipe_name: &str,
-) -> std::io::Result<()> {
+    options: std::sync::Arc<pyorion_options::runtime::RuntimeOptions>,
+    on_listening: impl FnOnce(),
+) -> std::io::Result<()>
+where
+    P: crate::connections::handler::RequestSink + Clone + Send + Sync + 'static,
+{
     use tokio::net::windows::named_pipe::ServerOptions;
     use windows_sys::Win32::Security::{
         InitializeSecurityDescriptor, SetSecurityDescriptorDacl, SECURITY_ATTRIBUTES,
@@ -40,12 +45,15 @@ pub async fn platform_main(
         unsafe {
             ServerOptions::new()
                 .first_pipe_instance(true)
-                .create_with_security_attributes_raw(&pipe_full_name, &mut sa as *mut _ as _)
-                .unwrap()
+                .create_with_security_attributes_raw(&pipe_full_name, &mut sa as *mut _ as _)?
         }
     }; //sd + sa are released again here → Future remains Send
 
     // println!("Secure Named Pipe Server runs on{}", pipe_full_name);
+    on_listening();
+
+    // Client tasks live in the set so dropping the listener aborts them too
+    let mut clients = tokio::task::JoinSet::new();
 
     loop {
         server.connect().await?;
@@ -54,12 +62,23 @@ pub async fn platform_main(
 
         let proxy = proxy.clone();
         let pending = pending.clone();
+        let options = options.clone();
+
+        // Reap finished clients; the rest are aborted with the set
+        while clients.try_join_next().is_some() {}
+
+        if clients.len() >= options.max_connections() {
+            let max_connections = options.max_connections();
+            let _ = crate::connections::handler::refuse(&mut inner, max_connections).await;
+            continue;
+        }
 
-        tokio::spawn(async move {
+        clients.spawn(async move {
             if let Err(e) =
-                crate::connections::handler::handle_client(&mut inner, proxy, pending).await
+                crate::connections::handler::handle_client(&mut inner, proxy, pending, options)
+                    .await
             {
-                eprintln!("[Pipe] Client error: {:?}", e);
+                tracing::warn!(kind = e.kind(), error = %e, "named pipe client error");
             }
         });
     }
@@ -15,6 +15,9 @@ use crate::utils::{arc_mut, ArcMut};
 pub struct AppContext {
     first_id: Option<WindowId>,
     pub window: Arc<Mutex<HashMap<WindowId, (Arc<Window>, Arc<wry::WebView>)>>>,
+    // Kept alive as long as the webviews created from them
+    web_contexts: Vec<std::rc::Rc<wry::WebContext>>,
+    configs: HashMap<WindowId, crate::window::builder::WebViewConfig>,
 }
 
 impl AppContext {
@@ -22,6 +25,8 @@ impl AppContext {
         Ok(arc_mut(Self {
             first_id: None,
             window: Arc::new(Mutex::new(HashMap::new())),
+            web_contexts: Vec::new(),
+            configs: HashMap::new(),
         }))
     }
 
@@ -48,7 +53,8 @@ impl AppContext {
                         if let Err(e) = mp_event.call_method0(py, "set") {
                             e.print(py);
                         }
-                        py.check_signals().unwrap();
+                        // A pending Ctrl-C may be why we are closing: never panic on it
+                        let _ = py.check_signals();
                     });
                     *flow = ControlFlow::Exit;
                 }
@@ -61,6 +67,51 @@ impl AppContext {
         }
     }
 
+    // Drops every window and its WebView
+    pub fn clear_windows(&mut self) -> Result<()> {
+        self.first_id = None;
+        let windows = std::mem::take(
+            &mut *self
+                .window
+                .lock()
+                .map_err(|e| anyhow!("Mutex poison error: {}", e))?,
+        );
+        // WebViews go before the windows hosting them
+        for (_, (window, webview)) in windows {
+            drop(webview);
+            drop(window);
+        }
+        self.web_contexts.clear();
+        self.configs.clear();
+        Ok(())
+    }
+
+    // Adds a built frame, keeping its web context alive until the windows are cleared.
+    // Labels identify webviews towards Python and must be unique.
+    pub fn add_frame(&mut self, frame: crate::window::Frame) -> Result<()> {
+        if self
+            .configs
+            .values()
+            .any(|config| config.label == frame.config.label)
+        {
+            return Err(anyhow!(
+                "a webview labelled {:?} already exists",
+                frame.config.label
+            ));
+        }
+        self.add_window(frame.id, Arc::new(frame.window), Arc::new(frame.webview));
+        self.web_contexts.push(std::rc::Rc::new(frame.web_context));
+        self.configs.insert(frame.id, frame.config);
+        Ok(())
+    }
+
+    // Returns the effective webview settings of the first window
+    pub fn get_webview_config(&self) -> Result<crate::window::builder::WebViewConfig> {
+        self.first_id
+            .and_then(|id| self.configs.get(&id).cloned())
+            .ok_or_else(|| anyhow!("No WebView ID set"))
+    }
+
     // Method for adding a window and WebViews
     pub fn add_window(&mut self, id: WindowId, window: Arc<Window>, webview: Arc<wry::WebView>) {
         let mut guard = self
@@ -90,8 +141,15 @@ impl AppContext {
         }
     }
 
+    // Dispatches a native event to the first window's WebView, tagged with its label
+    pub fn emit(&self, event: &str, payload: &serde_json::Value) -> Result<()> {
+        let webview = self.get_webview()?;
+        let label = self.get_webview_config()?.label;
+        webview.evaluate_script(&crate::utils::emit_script(event, payload, &label))?;
+        Ok(())
+    }
+
     // Returns the WebView for the first window
-    #[allow(dead_code)]
     pub fn get_webview(&self) -> Result<Arc<wry::WebView>> {
         if let Some(id) = self.first_id {
             let guard = self
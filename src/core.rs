@@ -4,16 +4,95 @@
 
 use anyhow::Result;
 use pyo3::Python;
-use pyorion_options::window::WindowOptions;
-use std::sync::Arc;
+use pyorion_options::{
+    runtime::{RuntimeFlavor, RuntimeOptions},
+    window::WindowOptions,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tao::{
+    event::StartCause, event_loop::ControlFlow, platform::run_return::EventLoopExtRunReturn,
+};
 
 use crate::{
-    api_manager::{ApiManager, ApiResponse},
+    api_manager::{ApiError, ApiManager, ApiResponse, ErrorKind},
     context::AppContext,
     lock,
-    utils::{ArcMut, FrameEventLoop, FrameEventLoopProxy, PendingMap, UserEvent},
+    utils::{ArcMut, FrameEventLoop, FrameEventLoopProxy, Milestone, PendingMap, UserEvent},
+    window::gesture::Gesture,
+    window::state::{StateWatch, WindowState},
 };
 
+/// How often the event loop checks whether Python fired the close event.
+const CLOSE_EVENT_POLL: Duration = Duration::from_millis(250);
+
+/// Thread driving a current-thread runtime until told to stop.
+pub(crate) struct RuntimeDriver {
+    stop: tokio::sync::oneshot::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+impl RuntimeDriver {
+    /// Lets the driving thread return and waits for it.
+    pub(crate) fn stop(self) {
+        let _ = self.stop.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Builds the async runtime serving connections as configured.
+///
+/// A multi-thread runtime runs its own workers. A current-thread runtime only
+/// makes progress inside `block_on`, so it gets a background thread that
+/// drives it until [`RuntimeDriver::stop`] is called.
+pub(crate) fn build_runtime(
+    options: &RuntimeOptions,
+) -> Result<(Arc<tokio::runtime::Runtime>, Option<RuntimeDriver>)> {
+    match options.runtime_flavor() {
+        RuntimeFlavor::MultiThread => {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(options.worker_threads())
+                .thread_name("pyorion-worker")
+                .enable_all()
+                .build()?;
+            Ok((Arc::new(rt), None))
+        }
+        RuntimeFlavor::CurrentThread => {
+            let rt = Arc::new(
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()?,
+            );
+            let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+            let thread = std::thread::Builder::new()
+                .name("pyorion-runtime".to_string())
+                .spawn({
+                    let rt = rt.clone();
+                    move || {
+                        let _ = rt.block_on(stopped);
+                    }
+                })?;
+            Ok((rt, Some(RuntimeDriver { stop, thread })))
+        }
+    }
+}
+
+/// Startup milestones seen so far; `ready` is emitted once all are in.
+#[derive(Default)]
+struct Readiness {
+    listening: bool,
+    page_loaded: bool,
+    emitted: bool,
+}
+
+/// Throttling state of the window drag events.
+struct Gestures {
+    moved: Gesture,
+    resized: Gesture,
+}
+
 #[allow(dead_code)]
 pub struct App {
     api_manager: Arc<std::sync::Mutex<ApiManager>>,
@@ -22,6 +101,24 @@ pub struct App {
     pub proxy: FrameEventLoopProxy,
     response_map: PendingMap,
     pub ctx: ArcMut<AppContext>,
+    pub runtime_options: Arc<RuntimeOptions>,
+    connection: std::sync::Mutex<Option<tokio::task::JoinHandle<std::io::Result<()>>>>,
+    signals: Option<tokio::task::JoinHandle<()>>,
+    shut_down: crate::utils::Latch,
+    readiness: std::sync::Mutex<Readiness>,
+    ready_payload: serde_json::Value,
+    label: String,
+    window_id: tao::window::WindowId,
+    /// Deadline of a close waiting for `window.resolveClose`.
+    pending_close: std::sync::Mutex<Option<Instant>>,
+    driver: std::sync::Mutex<Option<RuntimeDriver>>,
+    gestures: std::sync::Mutex<Gestures>,
+    /// Inner size constraints in effect; tao cannot report them back.
+    size_constraints: std::sync::Mutex<tao::window::WindowSizeConstraints>,
+    /// Modifier keys last reported, sent along with forwarded key events.
+    modifiers: std::sync::Mutex<tao::keyboard::ModifiersState>,
+    focus_webview_on_show: bool,
+    window_state: std::sync::Mutex<StateWatch>,
 }
 
 impl App {
@@ -30,55 +127,102 @@ impl App {
         sock_cfg: Option<crate::assets::WebSocketConfig>,
         options: &WindowOptions,
         uds_name: String,
+        mut runtime_options: RuntimeOptions,
     ) -> Result<std::sync::Arc<App>> {
+        let api_manager = ApiManager::new();
+        {
+            let mut api_manager = lock!(api_manager)?;
+            api_manager.set_filter(runtime_options.apis.clone());
+            crate::api::register_api_instances(&mut api_manager);
+            runtime_options.api_namespaces = api_manager.namespaces();
+        }
+        let runtime_options = Arc::new(runtime_options);
         let proxy = event_loop.create_proxy();
 
-        let rt = std::sync::Arc::new(
-            tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()?,
-        );
+        let (rt, driver) = build_runtime(&runtime_options)?;
 
-        let (window_id, window, webview) =
-            crate::window::create_frame(&event_loop, options, sock_cfg)?;
+        let port = sock_cfg.as_ref().and_then(|cfg| cfg.port());
+        let mut size_constraints = tao::window::WindowSizeConstraints::default();
+        if let Some(size) = &options.min_inner_size {
+            let (width, height) = size.pixel_units();
+            size_constraints.min_width = Some(width);
+            size_constraints.min_height = Some(height);
+        }
+        if let Some(size) = &options.max_inner_size {
+            let (width, height) = size.pixel_units();
+            size_constraints.max_width = Some(width);
+            size_constraints.max_height = Some(height);
+        }
+        let mut options = options.clone();
+        if !runtime_options.devtools() {
+            options.webview.devtools = Some(false);
+        }
+        let frame = crate::window::create_frame(&event_loop, &options, sock_cfg, &proxy)?;
+        let initial_window_state = WindowState::of(&frame.window);
+        let window_id = frame.id;
+        let label = frame.config.label.clone();
+        let ready_payload = serde_json::json!({
+            "windowId": format!("{:?}", window_id),
+            "label": label,
+            "port": port,
+        });
 
         let ctx = AppContext::new()?;
 
         {
             let mut ctx_lock = lock!(ctx)?;
-            ctx_lock.add_window(window_id, Arc::new(window), Arc::new(webview));
+            ctx_lock.add_frame(frame)?;
         }
 
         let handle = rt.handle().clone();
 
         let cloned_proxy = proxy.clone();
 
-        let api_manager = ApiManager::new();
-        {
-            let mut api_manager = lock!(api_manager)?;
-            crate::api::register_api_instances(&mut api_manager);
-        }
+        let response_map: PendingMap =
+            Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+
+        let connection = rt.spawn(crate::connections::start_connection(
+            cloned_proxy.clone(),
+            response_map.clone(),
+            uds_name.to_string(),
+            runtime_options.clone(),
+        ));
+
+        let signals = runtime_options
+            .handle_signals()
+            .then(|| rt.spawn(Self::watch_signals(proxy.clone())));
 
         let app = Arc::new(Self {
             api_manager: api_manager.clone(),
             rt: rt.clone(),
             runtime_handel: std::sync::Arc::new(handle),
             proxy,
-            response_map: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+            response_map,
             ctx: ctx.clone(),
+            runtime_options,
+            connection: std::sync::Mutex::new(Some(connection)),
+            signals,
+            shut_down: Default::default(),
+            readiness: std::sync::Mutex::new(Readiness::default()),
+            ready_payload,
+            label,
+            window_id,
+            pending_close: std::sync::Mutex::new(None),
+            driver: std::sync::Mutex::new(driver),
+            gestures: std::sync::Mutex::new(Gestures {
+                moved: Gesture::new("moved", "moveEnd"),
+                resized: Gesture::new("resized", "resizeEnd"),
+            }),
+            size_constraints: std::sync::Mutex::new(size_constraints),
+            modifiers: std::sync::Mutex::new(Default::default()),
+            focus_webview_on_show: options.focus_webview_on_show(),
+            window_state: std::sync::Mutex::new(StateWatch::new(initial_window_state)),
         });
 
         {
             let mut m = lock!(api_manager).unwrap();
             m.bind_app_context(&app);
         }
-        let map = app.clone().response_map.clone();
-
-        rt.spawn(crate::connections::start_connection(
-            cloned_proxy.clone(),
-            map,
-            uds_name.to_string(),
-        ));
         Ok(app)
     }
 
@@ -90,46 +234,393 @@ impl App {
     pub fn app_context(&self) -> Result<std::sync::MutexGuard<'_, AppContext>> {
         lock!(self.ctx)
     }
+    /// Label of the webview, stamped on every response and event.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+    /// Native id of the window.
+    pub fn window_id(&self) -> tao::window::WindowId {
+        self.window_id
+    }
+    /// Takes the sender of a pending request so a [`Deferred`] handler can
+    /// answer from another thread or callback.
+    ///
+    /// Responses sent through it should be stamped with [`App::label`].
+    ///
+    /// [`Deferred`]: crate::api_manager::Deferred
+    pub fn responder(&self, key: u8) -> Option<tokio::sync::oneshot::Sender<ApiResponse>> {
+        self.response_map.lock().unwrap().remove(&key)
+    }
     #[allow(dead_code)]
     pub fn respond(&self, key: u8, response: ApiResponse) {
         if let Some(sender) = self.response_map.lock().unwrap().remove(&key) {
-            let _ = sender.send(response);
+            let _ = sender.send(response.with_label(&self.label));
         } else {
-            eprintln!("No transmitter found for key {}", key);
+            tracing::warn!(id = key, "no pending request for response");
+        }
+    }
+
+    /// Queues a named event for delivery to the frontend.
+    pub fn emit<S: Into<String>, T: serde::Serialize>(&self, event: S, payload: T) {
+        let payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+        let _ = self
+            .proxy
+            .send_event(UserEvent::Emit(event.into(), payload));
+    }
+
+    /// Records a startup milestone and emits `ready` once, as soon as the
+    /// listener accepts requests and the first page finished loading.
+    ///
+    /// The payload carries the `windowId`, the webview `label` and the
+    /// WebSocket `port`. The page's socket to Python may still be connecting
+    /// at that point; `invoke.js` holds events until it opens, so `ready`
+    /// reaches Python once it can also answer calls from the page.
+    fn reached(&self, milestone: Milestone) {
+        let Ok(mut readiness) = self.readiness.lock() else {
+            return;
+        };
+        match milestone {
+            Milestone::Listening => readiness.listening = true,
+            Milestone::PageLoaded => readiness.page_loaded = true,
+        }
+        if readiness.listening && readiness.page_loaded && !readiness.emitted {
+            readiness.emitted = true;
+            self.emit("ready", self.ready_payload.clone());
+        }
+    }
+
+    /// Holds a close request and emits `closeRequested` so Python can veto
+    /// it; repeated requests while one is pending are ignored.
+    fn hold_close(&self, timeout: Duration) {
+        let Ok(mut pending) = self.pending_close.lock() else {
+            return;
+        };
+        if pending.is_none() {
+            *pending = Some(Instant::now() + timeout);
+            self.emit(
+                "closeRequested",
+                serde_json::json!({ "timeoutMs": timeout.as_millis() as u64 }),
+            );
         }
     }
 
+    /// Settles a held close: `allow` closes the window, otherwise it stays
+    /// open. Returns whether a close was pending.
+    pub fn resolve_close(&self, allow: bool) -> Result<bool> {
+        let pending = lock!(self.pending_close)?.take().is_some();
+        if pending && allow {
+            let _ = self.proxy.send_event(UserEvent::Shutdown);
+        }
+        Ok(pending)
+    }
+
+    /// Loads `url` in the webview, e.g. a held navigation that was allowed.
+    pub fn navigate(&self, url: &str) {
+        let result = self
+            .app_context()
+            .and_then(|ctx| ctx.get_webview())
+            .and_then(|webview| Ok(webview.load_url(url)?));
+        if let Err(err) = result {
+            tracing::error!(%url, error = ?err, "failed to load url");
+        }
+    }
+
+    /// Called after the window was shown through the API; returns keyboard
+    /// focus to the webview when `focusWebviewOnShow` asks for it.
+    pub fn window_shown(&self) -> Result<()> {
+        if self.focus_webview_on_show {
+            self.app_context()?.get_webview()?.focus()?;
+        }
+        Ok(())
+    }
+
+    /// Inner size constraints last applied to the window.
+    pub fn size_constraints(&self) -> Result<tao::window::WindowSizeConstraints> {
+        Ok(*lock!(self.size_constraints)?)
+    }
+
+    /// Records a change of the inner size constraints applied to the window.
+    pub fn update_size_constraints(
+        &self,
+        update: impl FnOnce(&mut tao::window::WindowSizeConstraints),
+    ) -> Result<()> {
+        update(&mut *lock!(self.size_constraints)?);
+        Ok(())
+    }
+
+    /// Feeds a `moved` or `resized` change into its throttle.
+    fn gesture_changed(&self, pick: fn(&mut Gestures) -> &mut Gesture, payload: serde_json::Value) {
+        let Ok(mut gestures) = self.gestures.lock() else {
+            return;
+        };
+        let interval = self.runtime_options.window_event_interval();
+        for (event, payload) in pick(&mut gestures).change(payload, interval) {
+            self.emit(event, payload);
+        }
+    }
+
+    /// Emits the events of settled gestures and returns when to look again.
+    fn settle_gestures(&self) -> Option<Instant> {
+        let mut gestures = self.gestures.lock().ok()?;
+        let interval = self.runtime_options.window_event_interval();
+        let Gestures { moved, resized } = &mut *gestures;
+        let mut deadline = self.settle_window_state();
+        for gesture in [moved, resized] {
+            for (event, payload) in gesture.settle(interval) {
+                self.emit(event, payload);
+            }
+            deadline = match (deadline, gesture.deadline(interval)) {
+                (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                (a, b) => a.or(b),
+            };
+        }
+        deadline
+    }
+
+    /// Schedules a look at the window state after an event that may have
+    /// minimized, maximized or restored it.
+    fn window_state_touched(&self) {
+        if let Ok(mut watch) = self.window_state.lock() {
+            watch.touch(self.runtime_options.window_event_interval());
+        }
+    }
+
+    /// Emits `windowStateChanged` once a scheduled look finds a new state
+    /// and returns when to look again.
+    fn settle_window_state(&self) -> Option<Instant> {
+        let mut watch = self.window_state.lock().ok()?;
+        if let Ok(window) = self.app_context().and_then(|ctx| ctx.get_window()) {
+            if let Some(payload) = watch.settle(&window) {
+                self.emit("windowStateChanged", payload);
+            }
+        }
+        watch.deadline()
+    }
+
+    /// Wakes the event loop no later than `deadline`.
+    fn wake_at(control_flow: &mut ControlFlow, deadline: Option<Instant>) {
+        if let (ControlFlow::WaitUntil(at), Some(deadline)) = (*control_flow, deadline) {
+            if deadline < at {
+                *control_flow = ControlFlow::WaitUntil(deadline);
+            }
+        }
+    }
+
+    /// Whether a held close ran out of time without an answer.
+    fn close_expired(&self) -> bool {
+        let Ok(mut pending) = self.pending_close.lock() else {
+            return false;
+        };
+        match *pending {
+            Some(deadline) if deadline <= Instant::now() => {
+                *pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Waits for SIGINT/SIGTERM (Ctrl-C on Windows) and asks the event loop
+    /// to close, which takes the same path as closing the last window.
+    async fn watch_signals(proxy: FrameEventLoopProxy) {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+                return;
+            };
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+        }
+        #[cfg(not(unix))]
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+
+        tracing::info!("signal received, shutting down");
+        let _ = proxy.send_event(UserEvent::Shutdown);
+    }
+
+    /// Tears down everything the event loop left behind.
+    ///
+    /// Stops the connection listener (which aborts its in-flight client tasks
+    /// and unlinks the socket file), drops pending responders and releases the
+    /// webviews. Errors the listener ended with are surfaced here. Runs only
+    /// once, however many shutdown triggers fired.
+    fn shutdown(&self) -> Result<()> {
+        if !self.shut_down.trip() {
+            return Ok(());
+        }
+        if let Some(signals) = &self.signals {
+            signals.abort();
+        }
+
+        let listener = lock!(self.connection)?.take();
+        let result = match listener {
+            Some(listener) => {
+                listener.abort();
+                match self.rt.block_on(listener) {
+                    Ok(result) => result.map_err(anyhow::Error::from),
+                    Err(err) if err.is_cancelled() => Ok(()),
+                    Err(err) => Err(err.into()),
+                }
+            }
+            None => Ok(()),
+        };
+
+        crate::api::shutdown();
+        lock!(self.response_map)?.clear();
+        lock!(self.ctx)?.clear_windows()?;
+        if let Some(driver) = lock!(self.driver)?.take() {
+            driver.stop();
+        }
+        result
+    }
+
+    /// Whether Python already fired the shared close event.
+    fn close_event_fired(mp_event: &pyo3::Py<pyo3::PyAny>) -> bool {
+        Python::with_gil(|py| {
+            mp_event
+                .call_method0(py, "is_set")
+                .and_then(|fired| fired.extract::<bool>(py))
+                // A vanished manager means the parent is gone as well
+                .unwrap_or(true)
+        })
+    }
+
     pub fn run(
         self: Arc<Self>,
-        event_loop: FrameEventLoop,
+        mut event_loop: FrameEventLoop,
         _mp_event: pyo3::Py<pyo3::PyAny>,
     ) -> Result<()> {
         let api_manager = self.api_manager.clone();
         let ctx = self.ctx.clone();
         let this = self.clone();
 
-        event_loop.run(move |event, target, control_flow| {
-            *control_flow = tao::event_loop::ControlFlow::Wait;
-
+        event_loop.run_return(|event, target, control_flow| {
             match event {
-                tao::event::Event::WindowEvent { event, .. } => match event {
-                    tao::event::WindowEvent::CloseRequested => {
+                tao::event::Event::NewEvents(StartCause::Init) => {
+                    *control_flow = ControlFlow::WaitUntil(Instant::now() + CLOSE_EVENT_POLL);
+                }
+                tao::event::Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                    *control_flow = if Self::close_event_fired(&_mp_event) {
+                        ControlFlow::Exit
+                    } else {
+                        ControlFlow::WaitUntil(Instant::now() + CLOSE_EVENT_POLL)
+                    };
+                    Self::wake_at(control_flow, this.settle_gestures());
+                    if this.close_expired() {
+                        tracing::info!("close request was not answered in time, closing");
                         let mp_event = Python::with_gil(|py| _mp_event.clone_ref(py));
                         let _ = ctx.lock().unwrap().close_window(mp_event, control_flow);
                     }
+                    for url in crate::window::navigation::expired() {
+                        tracing::info!(%url, "navigation was not answered in time, allowing");
+                        this.navigate(&url);
+                    }
+                    Self::wake_at(control_flow, crate::window::navigation::deadline());
+                }
+                tao::event::Event::WindowEvent { event, .. } => match event {
+                    tao::event::WindowEvent::CloseRequested => {
+                        if let Some(timeout) = this.runtime_options.close_confirm_timeout() {
+                            this.hold_close(timeout);
+                        } else {
+                            let mp_event = Python::with_gil(|py| _mp_event.clone_ref(py));
+                            let _ = ctx.lock().unwrap().close_window(mp_event, control_flow);
+                        }
+                    }
+                    tao::event::WindowEvent::Moved(position) => {
+                        this.gesture_changed(
+                            |gestures| &mut gestures.moved,
+                            serde_json::json!({ "x": position.x, "y": position.y }),
+                        );
+                        this.window_state_touched();
+                        Self::wake_at(control_flow, this.settle_gestures());
+                    }
+                    // Reports the size the platform applied, after clamping
+                    tao::event::WindowEvent::Resized(size) => {
+                        this.gesture_changed(
+                            |gestures| &mut gestures.resized,
+                            serde_json::json!(pyorion_options::window::Dimensions::from(size)),
+                        );
+                        this.window_state_touched();
+                        Self::wake_at(control_flow, this.settle_gestures());
+                    }
+                    tao::event::WindowEvent::KeyboardInput { event, .. }
+                        if this.runtime_options.forward_keys() =>
+                    {
+                        let modifiers = this.modifiers.lock().map(|m| *m).unwrap_or_default();
+                        let (name, payload) = crate::window::keys::key_event(&event, modifiers);
+                        this.emit(name, payload);
+                    }
+                    tao::event::WindowEvent::ModifiersChanged(state) => {
+                        if let Ok(mut modifiers) = this.modifiers.lock() {
+                            *modifiers = state;
+                        }
+                        if this.runtime_options.forward_keys() {
+                            this.emit("modifiersChanged", crate::window::keys::modifiers(state));
+                        }
+                    }
+                    tao::event::WindowEvent::Focused(focused) => {
+                        this.emit("focusChanged", serde_json::json!({ "focused": focused }));
+                        this.window_state_touched();
+                        Self::wake_at(control_flow, this.settle_gestures());
+                    }
+                    tao::event::WindowEvent::ThemeChanged(theme) => {
+                        this.emit("themeChanged", pyorion_options::window::Theme::from(theme));
+                    }
+                    tao::event::WindowEvent::ScaleFactorChanged {
+                        scale_factor,
+                        new_inner_size,
+                    } => {
+                        this.emit(
+                            "scaleFactorChanged",
+                            serde_json::json!({
+                                "scaleFactor": scale_factor,
+                                "newInnerSize": pyorion_options::window::Dimensions {
+                                    width: new_inner_size.width,
+                                    height: new_inner_size.height,
+                                },
+                            }),
+                        );
+                    }
                     _ => {}
                 },
                 tao::event::Event::UserEvent(event) => match event {
                     UserEvent::Request(req) => {
+                        let _span =
+                            tracing::debug_span!("handle", id = req.0, api = %req.1).entered();
                         let mut manager = api_manager.lock().unwrap();
+                        let id = req.0;
                         match manager.call(req, target, control_flow) {
-                            Ok(res) => this.respond(res.0, res),
+                            Ok(Some(res)) => this.respond(res.0, res),
+                            Ok(None) => {}
                             Err(err) => {
-                                eprintln!("API call failed: {:?}", err);
-                                // evtl. ein ApiResponse mit Fehler zurückschicken
+                                tracing::error!(error = ?err, "API call failed");
+                                // Answer right away instead of letting the client time out
+                                this.respond(
+                                    id,
+                                    ApiError::new(ErrorKind::Internal, err.to_string())
+                                        .into_response(id),
+                                );
                             }
                         };
                     }
+                    UserEvent::Emit(name, payload) => {
+                        if let Err(err) = ctx.lock().unwrap().emit(&name, &payload) {
+                            tracing::error!(event = %name, error = ?err, "failed to emit event");
+                        }
+                    }
+                    UserEvent::Milestone(milestone) => this.reached(milestone),
+                    UserEvent::DragWindow => {
+                        if let Ok(window) = ctx.lock().unwrap().get_window() {
+                            if let Err(err) = window.drag_window() {
+                                tracing::debug!(error = %err, "failed to start dragging");
+                            }
+                        }
+                    }
                     UserEvent::Shutdown => {
                         let mp_event = Python::with_gil(|py| _mp_event.clone_ref(py));
                         let _ = ctx.lock().unwrap().close_window(mp_event, control_flow);
@@ -138,5 +629,7 @@ impl App {
                 _ => {}
             }
         });
+
+        self.shutdown()
     }
 }
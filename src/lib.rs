@@ -11,52 +11,155 @@
 use anyhow::Result;
 use pyo3::prelude::*;
 
-use crate::utils::FrameEventLoopBuilder;
 mod api;
 mod api_manager;
 mod assets;
 mod connections;
 mod context;
 mod core;
+mod logging;
+mod metrics;
 mod utils;
 mod window;
 
+/// Set while a webframe runs in this process.
+///
+/// The native event loop can only exist once per process (GTK and AppKit
+/// are bound to a single UI thread), so each webframe needs a process of its
+/// own; `run_native_runtime` spawns one per launch.
+static FRAME_RUNNING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Clears [`FRAME_RUNNING`] however `create_webframe` returns.
+struct FrameGuard;
+
+impl FrameGuard {
+    fn acquire() -> Result<Self> {
+        use std::sync::atomic::Ordering;
+        if FRAME_RUNNING
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            anyhow::bail!(
+                "A webframe is already running in this process; start each webframe in a process of its own"
+            );
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        FRAME_RUNNING.store(false, std::sync::atomic::Ordering::Release);
+    }
+}
+
 #[pyfunction]
+#[pyo3(signature = (config, sock_cfg, uds_name, close_event, runtime_cfg=None, auth_token=None))]
 fn create_webframe(
     config: String,
     sock_cfg: Option<String>,
     uds_name: String,
     close_event: Py<PyAny>,
+    runtime_cfg: Option<String>,
+    auth_token: Option<String>,
 ) -> Result<()> {
-    let options: &pyorion_options::window::WindowOptions = &serde_json::from_str(&config)?;
+    let _guard = FrameGuard::acquire()?;
+    let mut runtime_options: pyorion_options::runtime::RuntimeOptions = match runtime_cfg {
+        Some(s) => serde_json::from_str(&s)?,
+        None => Default::default(),
+    };
+    runtime_options.auth_token = auth_token;
+    logging::init(&runtime_options)?;
+
+    let config: serde_json::Value = serde_json::from_str(&config)?;
+    let unknown = pyorion_options::strict::check_window_fields(
+        &config,
+        runtime_options.strict_options.unwrap_or(false),
+    )?;
+    if !unknown.is_empty() {
+        tracing::warn!(options = %unknown.join(", "), "ignoring unknown window options");
+    }
+    let options: &pyorion_options::window::WindowOptions = &serde_json::from_value(config)?;
+    options.validate()?;
 
     let sock_cfg_json: Option<assets::WebSocketConfig> = match sock_cfg {
-        Some(s) => Some(serde_json::from_str(&s)?),
+        Some(s) => Some(
+            serde_json::from_str::<assets::WebSocketConfig>(&s)?
+                .validate()?
+                .with_tls()?,
+        ),
         None => None,
     };
 
-    let mut event_loop = FrameEventLoopBuilder::with_user_event().build();
-    let app = core::App::new(&mut event_loop, sock_cfg_json, options, uds_name)?;
+    let mut event_loop = window::builder::FrameBuilder::event_loop(options);
+    let app = core::App::new(
+        &mut event_loop,
+        sock_cfg_json,
+        options,
+        uds_name,
+        runtime_options,
+    )?;
     app.run(event_loop, close_event)
 }
 
 pub fn get_pyorion_version() -> &'static str {
-    // Mapping Cargo versioning (e.g., "1.0-alpha1") to Python's PEP 440 format (e.g., "1.0.0a1")
-    // This conversion is a simplified compatibility adjustment and covers most common cases.
-
     static PYFRAME_VERSION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
 
-    PYFRAME_VERSION.get_or_init(|| {
-        env!("CARGO_PKG_VERSION")
-            .replace("-alpha", "a")
-            .replace("-beta", "b")
-    })
+    PYFRAME_VERSION.get_or_init(|| pep440(env!("CARGO_PKG_VERSION")))
+}
+
+/// Maps a Cargo version to Python's PEP 440 format.
+///
+/// `1.0.0-alpha.2` becomes `1.0.0a2`, `-beta`/`-rc` map to `b`/`rc`,
+/// `-post`/`-dev` to `.postN`/`.devN`, and `+build` metadata is dropped
+/// since PEP 440 local versions are not allowed on PyPI. Unknown
+/// prerelease tags fall back to `.dev0`; only the first tag of each kind
+/// is kept so the result stays a valid version.
+fn pep440(version: &str) -> String {
+    let version = version.split('+').next().unwrap_or(version);
+    let Some((release, tags)) = version.split_once('-') else {
+        return version.to_string();
+    };
+
+    let (mut pre, mut post, mut dev) = (None, None, None);
+    let mut parts = tags.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let tag = part.trim_end_matches(|c: char| c.is_ascii_digit());
+        let mut number = &part[tag.len()..];
+        if number.is_empty() {
+            if let Some(next) = parts.next_if(|p| p.bytes().all(|b| b.is_ascii_digit())) {
+                number = next;
+            }
+        }
+        let number = number.parse::<u64>().unwrap_or(0);
+        match tag.to_ascii_lowercase().as_str() {
+            "alpha" | "a" => pre = pre.or(Some(format!("a{}", number))),
+            "beta" | "b" => pre = pre.or(Some(format!("b{}", number))),
+            "rc" | "c" | "pre" | "preview" => pre = pre.or(Some(format!("rc{}", number))),
+            "post" => post = post.or(Some(number)),
+            "dev" => dev = dev.or(Some(number)),
+            _ => dev = dev.or(Some(0)),
+        }
+    }
+
+    let mut out = release.to_string();
+    if let Some(pre) = pre {
+        out.push_str(&pre);
+    }
+    if let Some(post) = post {
+        out.push_str(&format!(".post{}", post));
+    }
+    if let Some(dev) = dev {
+        out.push_str(&format!(".dev{}", dev));
+    }
+    out
 }
 
 /// A Python module implemented in Rust.
 #[pymodule]
 fn _pyorion(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("__version__", get_pyorion_version())?;
+    m.add("INVOKE_SCRIPT", assets::_COMMAND_SCRIPT)?;
     m.add_function(wrap_pyfunction!(create_webframe, m)?)?;
     m.add_function(wrap_pyfunction!(
         crate::connections::send_event_over_platform,
@@ -64,3 +167,24 @@ fn _pyorion(m: &Bound<'_, PyModule>) -> PyResult<()> {
     )?)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::pep440;
+
+    #[test]
+    fn pre_releases_map_to_pep440() {
+        assert_eq!(pep440("1.2.0-rc.1"), "1.2.0rc1");
+        assert_eq!(pep440("1.2.0-alpha.2"), "1.2.0a2");
+        assert_eq!(pep440("1.2.0-beta3"), "1.2.0b3");
+    }
+
+    #[test]
+    fn dev_post_and_local_parts_map_to_pep440() {
+        assert_eq!(pep440("1.2.0-dev"), "1.2.0.dev0");
+        assert_eq!(pep440("1.2.0-post.3"), "1.2.0.post3");
+        assert_eq!(pep440("1.2.0+sha.abc123"), "1.2.0");
+        assert_eq!(pep440("1.2.0-rc.1+sha.abc123"), "1.2.0rc1");
+        assert_eq!(pep440("1.2.0"), "1.2.0");
+    }
+}
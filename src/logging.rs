@@ -0,0 +1,29 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use anyhow::Result;
+use pyorion_options::runtime::{LogFormat, RuntimeOptions};
+use tracing_subscriber::filter::LevelFilter;
+
+/// Installs the global `tracing` subscriber writing to stderr.
+///
+/// The webframe runs in its own process, so its stderr is the channel logs
+/// travel on; with the `json` format every line is one object Python can
+/// parse and hand to `logging`. A subscriber that is already installed is
+/// kept as is.
+pub fn init(options: &RuntimeOptions) -> Result<()> {
+    let level: LevelFilter = options
+        .log_level()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid log level {:?}", options.log_level()))?;
+
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr);
+    let _ = match options.log_format() {
+        LogFormat::Text => builder.try_init(),
+        LogFormat::Json => builder.json().with_current_span(true).try_init(),
+    };
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use crate::api_manager::{Code, ErrorKind};
+
+/// Bridge-wide counters, reported by `system.metrics`.
+pub static METRICS: Metrics = Metrics::new();
+
+/// Lock-free request and response counters.
+///
+/// Per-API call counts live in the [`ApiManager`], which is locked while a
+/// handler runs anyway.
+///
+/// [`ApiManager`]: crate::api_manager::ApiManager
+pub struct Metrics {
+    requests_received: AtomicU64,
+    responses_sent: AtomicU64,
+    /// Error responses, indexed like [`ErrorKind::ALL`].
+    errors: [AtomicU64; ErrorKind::ALL.len()],
+    handler_calls: AtomicU64,
+    handler_micros: AtomicU64,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            requests_received: AtomicU64::new(0),
+            responses_sent: AtomicU64::new(0),
+            errors: [const { AtomicU64::new(0) }; ErrorKind::ALL.len()],
+            handler_calls: AtomicU64::new(0),
+            handler_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a frame read from a client.
+    pub fn request_received(&self) {
+        self.requests_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a response written to a client, and its error kind if any.
+    pub fn response_sent(&self, code: Code) {
+        self.responses_sent.fetch_add(1, Ordering::Relaxed);
+        if code != 0 {
+            let kind = ErrorKind::from_code(code);
+            if let Some(i) = ErrorKind::ALL.iter().position(|k| *k == kind) {
+                self.errors[i].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Records how long a handler ran on the event loop.
+    pub fn handler_ran(&self, elapsed: Duration) {
+        self.handler_calls.fetch_add(1, Ordering::Relaxed);
+        self.handler_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// JSON snapshot of all counters plus the per-API `calls`.
+    ///
+    /// Errors are keyed by response code and only listed once they occurred.
+    pub fn snapshot(&self, calls: &HashMap<String, u64>) -> serde_json::Value {
+        let errors: serde_json::Map<String, serde_json::Value> = ErrorKind::ALL
+            .iter()
+            .zip(&self.errors)
+            .map(|(kind, count)| (kind.code(), count.load(Ordering::Relaxed)))
+            .filter(|(_, count)| *count > 0)
+            .map(|(code, count)| (code.to_string(), count.into()))
+            .collect();
+        let handler_calls = self.handler_calls.load(Ordering::Relaxed);
+        let avg_handler_latency_us = self
+            .handler_micros
+            .load(Ordering::Relaxed)
+            .checked_div(handler_calls)
+            .unwrap_or(0);
+        serde_json::json!({
+            "requestsReceived": self.requests_received.load(Ordering::Relaxed),
+            "responsesSent": self.responses_sent.load(Ordering::Relaxed),
+            "errors": errors,
+            "handlerCalls": handler_calls,
+            "avgHandlerLatencyUs": avg_handler_latency_us,
+            "calls": calls,
+        })
+    }
+}
@@ -17,13 +17,34 @@ pub type FrameEventLoopBuilder = EventLoopBuilder<UserEvent>;
 pub type FrameEventLoopProxy = EventLoopProxy<UserEvent>;
 #[allow(dead_code)]
 pub type FrameWindowTarget = EventLoopWindowTarget<UserEvent>;
+/// Senders of the requests in flight, keyed by request id.
+///
+/// One map is shared by every connection, so an id has to be unique across
+/// clients rather than per connection. A request reusing an id that is still
+/// in flight is refused with `409`; with one client numbering its requests
+/// that only happens when separate clients pick the same id at once.
 #[allow(dead_code)]
 pub type PendingMap = Arc<Mutex<HashMap<u8, tokio::sync::oneshot::Sender<ApiResponse>>>>;
 #[allow(dead_code)]
 pub enum UserEvent {
     Request(ApiRequest),
+    /// A named event (with JSON payload) to forward to the frontend.
+    Emit(String, Value),
+    /// A startup step on the way to the `ready` event.
+    Milestone(Milestone),
+    /// The primary button went down on a drag region.
+    DragWindow,
     Shutdown,
 }
+
+/// Startup steps that together make the app ready for API calls.
+#[derive(Debug, Clone, Copy)]
+pub enum Milestone {
+    /// The connection listener accepts requests.
+    Listening,
+    /// The webview finished loading its first page.
+    PageLoaded,
+}
 #[allow(dead_code)]
 pub type ArcMut<T> = Arc<Mutex<T>>;
 #[allow(dead_code)]
@@ -58,6 +79,18 @@ impl IdCounter {
     }
 }
 
+/// Lets something run exactly once, however often and from however many
+/// threads it is triggered.
+#[derive(Default)]
+pub struct Latch(std::sync::atomic::AtomicBool);
+
+impl Latch {
+    /// Returns `true` for the first caller only.
+    pub fn trip(&self) -> bool {
+        !self.0.swap(true, std::sync::atomic::Ordering::SeqCst)
+    }
+}
+
 #[macro_export]
 macro_rules! unsafe_impl_sync_send {
     ($type:ty) => {
@@ -125,7 +158,7 @@ macro_rules! logical_try {
 macro_rules! log_if_err {
     ($result:expr) => {
         if let Err(e) = $result {
-            println!("[Error]: {}", e);
+            tracing::error!(error = %e);
         }
     };
 }
@@ -133,14 +166,14 @@ macro_rules! log_if_err {
 #[macro_export]
 macro_rules! log {
     ($result:expr) => {
-        println!("[Info]: {}", $result);
+        tracing::info!("{}", $result);
     };
 }
 
 #[macro_export]
 macro_rules! log_err {
     ($result:expr) => {
-        println!("[Error]: {}", $result);
+        tracing::error!(error = %$result);
     };
 }
 #[allow(dead_code)]
@@ -170,6 +203,29 @@ macro_rules! try_or_log_err {
         }
     };
 }
+/// Serializes `value` as a JavaScript expression.
+///
+/// JSON is a JS literal once U+2028/U+2029 are escaped, which older engines
+/// treat as line breaks inside strings; quotes and backslashes are already
+/// escaped by `serde_json`, so the value cannot break out of the literal.
+pub fn js_literal(value: &Value) -> String {
+    value
+        .to_string()
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+/// Builds the script that dispatches a native event inside the webview.
+///
+/// The event is delivered as a `pyorion:event` DOM event whose `detail`
+/// is `{ event, payload, label }`; the injected bootstrap forwards it to
+/// Python, where `label` routes it to the right window handler.
+pub fn emit_script(event: &str, payload: &Value, label: &str) -> String {
+    let detail = serde_json::json!({ "event": event, "payload": payload, "label": label });
+    format!(
+        "window.dispatchEvent(new CustomEvent(\"pyorion:event\", {{ detail: {} }}));",
+        js_literal(&detail)
+    )
+}
 #[allow(dead_code)]
 pub fn url_join(left: &str, right: &str) -> String {
     if right.is_empty() {
@@ -354,34 +410,6 @@ impl MimeType {
     }
 }
 
-#[allow(dead_code)]
-fn get_wry_response(
-    request: wry::http::Request<Vec<u8>>,
-    index_page: Option<String>, // Default index page filename
-    root: &str,
-) -> Result<wry::http::Response<Vec<u8>>, Box<dyn std::error::Error>> {
-    let path = request.uri().path();
-    let root = std::path::PathBuf::from(root);
-    let file_path: String = if path == "/" {
-        match index_page {
-            Some(index) => index,
-            None => "index.html".to_string(),
-        }
-    } else {
-        path[1..].to_string()
-    };
-
-    let content = std::fs::read(std::fs::canonicalize(root.join(&file_path))?)?;
-
-    // Dynamically determine MIME
-    let mime_type = MimeType::parse_from_uri(&file_path).to_string();
-    // Create and return the HTTP response
-    wry::http::Response::builder()
-        .header(wry::http::header::CONTENT_TYPE, mime_type)
-        .body(content)
-        .map_err(Into::into)
-}
-
 fn split_root_and_index(input: &str) -> Result<(String, String), String> {
     if input.trim().is_empty() {
         return Err("Path must not be empty".to_string());
@@ -416,52 +444,56 @@ fn split_root_and_index(input: &str) -> Result<(String, String), String> {
     Ok((root_path, index_page))
 }
 
+/// First page a webview loads.
+pub enum StartPage {
+    Url(String),
+    Html(String),
+}
+
+impl StartPage {
+    pub fn load(self, builder: wry::WebViewBuilder<'_>) -> wry::WebViewBuilder<'_> {
+        match self {
+            StartPage::Url(url) => builder.with_url(url),
+            StartPage::Html(html) => builder.with_html(html),
+        }
+    }
+}
+
+/// Interprets `renderProtocol` and returns the page it implies: a remote
+/// URL, inline HTML, or a directory served over `app://`.
 pub fn render_protocol<'a>(
-    mut builder: wry::WebViewBuilder<'a>,
+    builder: wry::WebViewBuilder<'a>,
     root_path: Option<String>,
-) -> wry::WebViewBuilder<'a> {
+    headers: wry::http::HeaderMap,
+) -> (wry::WebViewBuilder<'a>, Option<StartPage>) {
     let main_root = root_path.unwrap_or_else(|| ".".to_string());
 
     if main_root.starts_with("http://") || main_root.starts_with("https://") {
         // URL
-        return builder.with_url(&main_root);
+        return (builder, Some(StartPage::Url(main_root)));
     }
 
     if main_root.contains("<html>") || main_root.contains("<!DOCTYPE html>") {
-        return builder.with_html(&main_root);
+        return (builder, Some(StartPage::Html(main_root)));
     }
 
     let (main_root_clone, index_page) = match split_root_and_index(&main_root) {
         Ok((root, index)) => (root, index),
         Err(e) => {
-            eprintln!("❌ Error in split root and index: {}", e);
-            return builder; // abort, build WebView without protocol
+            tracing::error!(error = %e, "failed to split root and index");
+            return (builder, None); // abort, build WebView without protocol
         }
     };
-    builder = builder.with_asynchronous_custom_protocol(
-        "wry".into(),
-        move |_webview_id, request, responder| {
-            let response = get_wry_response(
-                request,
-                Some(index_page.clone()), // Standard Index
-                &main_root_clone,         // Root directory
-            );
-
-            match response {
-                Ok(http_response) => responder.respond(http_response),
-                Err(e) => responder.respond(
-                    wry::http::Response::builder()
-                        .header(wry::http::header::CONTENT_TYPE, "text/plain")
-                        .status(500)
-                        .body(e.to_string().as_bytes().to_vec())
-                        .unwrap(),
-                ),
-            }
-        },
+    let builder = crate::assets::protocol::register(
+        builder,
+        crate::assets::store::AssetStore::Directory(main_root_clone.into()),
+        index_page,
+        headers,
     );
-
-    let url = format!("wry://localhost");
-    builder.with_url(&url)
+    (
+        builder,
+        Some(StartPage::Url(crate::assets::protocol::app_url())),
+    )
 }
 
 // #[allow(dead_code)]
@@ -491,3 +523,25 @@ pub fn render_protocol<'a>(
 // },
 // }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latch_trips_exactly_once() {
+        let latch = Latch::default();
+        let tripped = std::sync::atomic::AtomicUsize::new(0);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    if latch.trip() {
+                        tripped.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    }
+                });
+            }
+        });
+        assert_eq!(tripped.into_inner(), 1);
+        assert!(!latch.trip());
+    }
+}
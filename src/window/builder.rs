@@ -8,18 +8,596 @@ use tao::{
     window::{Window, WindowBuilder},
 };
 
-use crate::utils::{render_protocol, FrameWindowTarget};
+use crate::utils::{
+    render_protocol, FrameEventLoop, FrameEventLoopBuilder, FrameEventLoopProxy, FrameWindowTarget,
+    StartPage, UserEvent,
+};
+
+/// Label of a webview created without one.
+pub const DEFAULT_LABEL: &str = "root_webview";
+
+/// Checks that `label` is a usable webview label: non-empty and made of
+/// ASCII letters, digits, `-`, `_`, `/` and `:` only.
+pub fn check_label(label: &str) -> anyhow::Result<()> {
+    let valid = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | ':');
+    if label.is_empty() || !label.chars().all(valid) {
+        anyhow::bail!(
+            "invalid webview label {:?}: use ASCII letters, digits, '-', '_', '/' and ':'",
+            label
+        );
+    }
+    Ok(())
+}
+
+/// Replaces everything but ASCII letters, digits and `-` so `name` is safe
+/// as a single path component.
+fn path_component(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Name the default data directory of the app is derived from: its
+/// `appId`, else its window title, else the name of the executable.
+fn app_name(options: &WindowOptions) -> String {
+    options
+        .app_id
+        .clone()
+        .or_else(|| options.title.clone())
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()?
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "app".to_string())
+}
+
+/// Resolves and creates the directory the web context keeps its data in.
+///
+/// Uses `dataDirectory` when set, otherwise a per-app and per-label
+/// directory under `base`, the user's local data dir
+/// (`<data>/pyorion/<app>/<label>`), so apps do not share storage.
+/// `None` leaves the choice to the webview when there is no `base`.
+fn data_directory(
+    options: &WindowOptions,
+    base: Option<std::path::PathBuf>,
+) -> anyhow::Result<Option<std::path::PathBuf>> {
+    let webview = &options.webview;
+    // WebView2 keeps its user data folder in the web context directory
+    let data_directory = webview
+        .webview2_data_dir
+        .as_ref()
+        .filter(|_| cfg!(target_os = "windows"))
+        .or(webview.data_directory.as_ref());
+    let dir = match data_directory {
+        Some(dir) => Some(std::path::PathBuf::from(dir)),
+        None => base.map(|base| {
+            let label = webview.label.as_deref().unwrap_or(DEFAULT_LABEL);
+            base.join("pyorion")
+                .join(path_component(&app_name(options)))
+                .join(path_component(label))
+        }),
+    };
+    if let Some(dir) = &dir {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            anyhow::anyhow!("Failed to create data directory {}: {}", dir.display(), e)
+        })?;
+    }
+    Ok(dir)
+}
+
+/// Background used for transparent windows and webviews without an explicit color.
+const TRANSPARENT_BACKGROUND: (u8, u8, u8, u8) = (0, 0, 0, 0);
+
+/// Returns the background color to apply, defaulting to fully transparent for
+/// transparent frames and warning when an opaque color defeats transparency.
+fn coherent_background(
+    transparent: bool,
+    color: Option<(u8, u8, u8, u8)>,
+    field: &str,
+) -> Option<(u8, u8, u8, u8)> {
+    if !transparent {
+        return color;
+    }
+    if matches!(color, Some((_, _, _, 255))) {
+        tracing::warn!(
+            field,
+            "transparent is enabled but the color is opaque; the window will not appear transparent"
+        );
+    }
+    color.or(Some(TRANSPARENT_BACKGROUND))
+}
+
+/// Makes the window transparent when only its webview asked to be, since a
+/// transparent webview in an opaque window still shows the window background.
+/// A window explicitly set opaque stays so, with a warning.
+pub fn coherent_transparency(options: &WindowOptions) -> std::borrow::Cow<'_, WindowOptions> {
+    if options.webview.transparent != Some(true) {
+        return std::borrow::Cow::Borrowed(options);
+    }
+    match options.transparent {
+        None => {
+            let mut options = options.clone();
+            options.transparent = Some(true);
+            std::borrow::Cow::Owned(options)
+        }
+        Some(false) => {
+            tracing::warn!(
+                "webview.transparent has no effect while the window is opaque; set transparent on the window too"
+            );
+            std::borrow::Cow::Borrowed(options)
+        }
+        Some(true) => std::borrow::Cow::Borrowed(options),
+    }
+}
+
+/// Background used for opaque dark-themed windows without an explicit color,
+/// so the frame does not flash white before the page paints.
+const DARK_BACKGROUND: (u8, u8, u8, u8) = (30, 30, 30, 255);
+
+/// Returns [`DARK_BACKGROUND`] when `window` resolved to the dark theme, either
+/// forced through `theme` or inherited from the system, and neither an explicit
+/// color nor transparency decides the background.
+fn theme_background(
+    window: &Window,
+    transparent: bool,
+    color: Option<(u8, u8, u8, u8)>,
+) -> Option<(u8, u8, u8, u8)> {
+    if color.is_some() || transparent || window.theme() != tao::window::Theme::Dark {
+        return None;
+    }
+    Some(DARK_BACKGROUND)
+}
+
+/// Why the webview could not be created, with a hint on how to fix it.
+///
+/// Relayed to Python as the message of the exception `create_webframe`
+/// raises.
+#[derive(Debug)]
+pub enum WebViewError {
+    /// The system webview engine is missing or could not be started.
+    RuntimeMissing {
+        engine: &'static str,
+        hint: &'static str,
+        source: wry::Error,
+    },
+    /// No display server to attach the window to.
+    NoDisplay(wry::Error),
+    /// Any other webview error.
+    Other(wry::Error),
+}
+
+impl From<wry::Error> for WebViewError {
+    fn from(err: wry::Error) -> Self {
+        match err {
+            #[cfg(target_os = "windows")]
+            wry::Error::WebView2Error(_) if wry::webview_version().is_err() => {
+                WebViewError::RuntimeMissing {
+                engine: "WebView2",
+                    hint: "install the Microsoft Edge WebView2 Runtime: https://developer.microsoft.com/microsoft-edge/webview2/",
+                    source: err,
+                }
+            }
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            wry::Error::X11DisplayNotFound => WebViewError::NoDisplay(err),
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            wry::Error::GlibError(_) | wry::Error::GlibBoolError(_) | wry::Error::MissingManager => {
+                WebViewError::RuntimeMissing {
+                    engine: "WebKitGTK",
+                    hint: "install WebKitGTK 4.1 (e.g. libwebkit2gtk-4.1-0 on Debian/Ubuntu, webkit2gtk4.1 on Fedora)",
+                    source: err,
+                }
+            }
+            err => WebViewError::Other(err),
+        }
+    }
+}
+
+impl std::fmt::Display for WebViewError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebViewError::RuntimeMissing { engine, hint, source } => write!(
+                f,
+                "Failed to start the {} webview ({}); {}",
+                engine, source, hint
+            ),
+            WebViewError::NoDisplay(source) => write!(
+                f,
+                "No display available for the webview ({}); run inside a graphical session or set DISPLAY",
+                source
+            ),
+            WebViewError::Other(source) => write!(f, "Failed to create the webview: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for WebViewError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WebViewError::RuntimeMissing { source, .. }
+            | WebViewError::NoDisplay(source)
+            | WebViewError::Other(source) => Some(source),
+        }
+    }
+}
+
+/// Webview settings as they took effect, returned by `webview.getConfig`.
+///
+/// Unset options are filled with the backend defaults, and flags a platform
+/// ignores are reported as `false` there (e.g. `hotkeysZoom` outside Windows).
+#[derive(serde::Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WebViewConfig {
+    pub label: String,
+    pub devtools: bool,
+    pub autoplay: bool,
+    pub clipboard: bool,
+    pub incognito: bool,
+    pub transparent: bool,
+    pub visible: bool,
+    pub focused: bool,
+    pub accept_first_mouse: bool,
+    pub hotkeys_zoom: bool,
+    pub file_drop: bool,
+    pub user_agent: Option<String>,
+    pub data_directory: Option<std::path::PathBuf>,
+}
+
+/// Initialization scripts of the webview in the order they run: the socket
+/// script and the opt-in command script built on it, the drag region script,
+/// then the user's scripts, so user code can rely on everything before it.
+fn initialization_scripts(
+    options: &WebViewOptions,
+    socket_script: Option<String>,
+) -> Vec<std::borrow::Cow<'_, str>> {
+    use std::borrow::Cow;
+
+    let mut scripts = Vec::new();
+    if let Some(socket_script) = socket_script {
+        scripts.push(Cow::Owned(socket_script));
+        if options.invoke_helper.unwrap_or(false) {
+            scripts.push(Cow::Borrowed(crate::assets::_COMMAND_SCRIPT));
+        }
+    }
+    if options.enable_drag_regions.unwrap_or(false) {
+        scripts.push(Cow::Borrowed(crate::assets::DRAG_REGION_SCRIPT));
+    }
+    let user_scripts = options
+        .initialization_script
+        .iter()
+        .chain(options.initialization_scripts.iter().flatten());
+    scripts.extend(user_scripts.map(|script| Cow::Borrowed(script.as_str())));
+    scripts
+}
+
+/// Forwards file drag-and-drop on the webview as `fileDrop` events.
+///
+/// The payload carries the `type` (`enter`, `over`, `drop` or `leave`), the
+/// dragged `paths` where known and the `position` relative to the webview.
+/// The webview keeps its default handling, so HTML drop targets still work.
+fn drag_drop_handler(proxy: FrameEventLoopProxy) -> impl Fn(wry::DragDropEvent) -> bool {
+    move |event| {
+        let (kind, paths, position) = match event {
+            wry::DragDropEvent::Enter { paths, position } => ("enter", paths, Some(position)),
+            wry::DragDropEvent::Over { position } => ("over", Vec::new(), Some(position)),
+            wry::DragDropEvent::Drop { paths, position } => ("drop", paths, Some(position)),
+            wry::DragDropEvent::Leave => ("leave", Vec::new(), None),
+            _ => return false,
+        };
+        let _ = proxy.send_event(UserEvent::Emit(
+            "fileDrop".to_string(),
+            serde_json::json!({
+                "type": kind,
+                "paths": paths,
+                "position": position.map(|(x, y)| serde_json::json!({ "x": x, "y": y })),
+            }),
+        ));
+        false
+    }
+}
+
+/// Converts a configured name → value map into validated HTTP headers.
+fn header_map(
+    map: Option<&std::collections::HashMap<String, String>>,
+) -> anyhow::Result<wry::http::HeaderMap> {
+    let mut headers = wry::http::HeaderMap::new();
+    for (k, v) in map.into_iter().flatten() {
+        headers.insert(
+            wry::http::HeaderName::from_bytes(k.as_bytes())?,
+            wry::http::HeaderValue::from_str(v)?,
+        );
+    }
+    Ok(headers)
+}
+
+/// Warns about every option in `options` that is set but has no effect on
+/// this platform.
+#[allow(dead_code)]
+fn warn_ignored(platform: &str, options: &[(&str, bool)]) {
+    for (option, _) in options.iter().filter(|(_, set)| *set) {
+        tracing::warn!(option, "only takes effect on {}, ignoring", platform);
+    }
+}
+
+/// Applies the WebView2 browser arguments; `insecure_tls` adds the one
+/// accepting any certificate.
+#[cfg(target_os = "windows")]
+fn webview2_options<'a>(
+    mut builder: wry::WebViewBuilder<'a>,
+    options: &WebViewOptions,
+    insecure_tls: bool,
+) -> wry::WebViewBuilder<'a> {
+    use wry::WebViewBuilderExtWindows;
+
+    // Setting arguments drops the ones wry passes by default
+    let mut args = options.webview2_args.clone().unwrap_or_else(|| {
+        vec!["--disable-features=msWebOOUI,msPdfOOUI,msSmartScreenProtection".to_string()]
+    });
+    if insecure_tls {
+        tracing::warn!("TLS certificate verification is off for the whole webview");
+        args.push("--ignore-certificate-errors".to_string());
+    }
+    if options.webview2_args.is_some() || insecure_tls {
+        builder = builder.with_additional_browser_args(args.join(" "));
+    }
+    builder
+}
+
+#[cfg(not(target_os = "windows"))]
+fn webview2_options<'a>(
+    builder: wry::WebViewBuilder<'a>,
+    options: &WebViewOptions,
+    _insecure_tls: bool,
+) -> wry::WebViewBuilder<'a> {
+    warn_ignored(
+        "Windows",
+        &[
+            ("webview2Args", options.webview2_args.is_some()),
+            ("webview2DataDir", options.webview2_data_dir.is_some()),
+        ],
+    );
+    builder
+}
+
+/// Trusts the backend certificate of the WebSocket connection in the
+/// webview's network session.
+#[cfg(target_os = "linux")]
+fn trust_backend(
+    webview: &wry::WebView,
+    tls: &crate::assets::TlsConfig,
+    host: &str,
+) -> anyhow::Result<()> {
+    use webkit2gtk::{WebContextExt, WebViewExt, WebsiteDataManagerExt};
+    use wry::WebViewExtUnix;
+
+    let context = webview
+        .webview()
+        .context()
+        .ok_or_else(|| anyhow::anyhow!("webview has no web context to apply TLS settings to"))?;
+    if let Some(path) = tls.ca_cert() {
+        let certificate = gtk::gio::TlsCertificate::from_file(path)
+            .map_err(|e| anyhow::anyhow!("Cannot load TLS caCert {}: {}", path.display(), e))?;
+        context.allow_tls_certificate_for_host(&certificate, host);
+    }
+    if tls.insecure_skip_verify() {
+        tracing::warn!(
+            host,
+            "TLS certificate verification is off for the whole webview"
+        );
+        if let Some(manager) = context.website_data_manager() {
+            manager.set_tls_errors_policy(webkit2gtk::TLSErrorsPolicy::Ignore);
+        }
+    }
+    Ok(())
+}
+
+/// Everything other platforms support is applied while building the
+/// webview, see `TlsConfig`.
+#[cfg(not(target_os = "linux"))]
+fn trust_backend(
+    _webview: &wry::WebView,
+    _tls: &crate::assets::TlsConfig,
+    _host: &str,
+) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// Applies the macOS-only window options.
+#[cfg(target_os = "macos")]
+fn macos_options(mut builder: WindowBuilder, options: &WindowOptions) -> WindowBuilder {
+    use tao::platform::macos::WindowBuilderExtMacOS;
+
+    if let Some(v) = options.titlebar_transparent {
+        builder = builder.with_titlebar_transparent(v);
+    }
+    if let Some(v) = options.full_size_content_view {
+        builder = builder.with_fullsize_content_view(v);
+    }
+    if let Some(v) = options.title_hidden {
+        builder = builder.with_title_hidden(v);
+    }
+    if let Some(v) = options.traffic_light_inset.clone() {
+        builder = builder.with_traffic_light_inset(Position::from(v));
+    }
+    builder
+}
+
+#[cfg(not(target_os = "macos"))]
+fn macos_options(builder: WindowBuilder, options: &WindowOptions) -> WindowBuilder {
+    warn_ignored(
+        "macOS",
+        &[
+            (
+                "titlebarTransparent",
+                options.titlebar_transparent.is_some(),
+            ),
+            (
+                "fullSizeContentView",
+                options.full_size_content_view.is_some(),
+            ),
+            ("titleHidden", options.title_hidden.is_some()),
+            ("trafficLightInset", options.traffic_light_inset.is_some()),
+        ],
+    );
+    builder
+}
+
+/// Applies the Windows-only window options.
+#[cfg(target_os = "windows")]
+fn windows_options(
+    mut builder: WindowBuilder,
+    options: &WindowOptions,
+) -> anyhow::Result<WindowBuilder> {
+    use tao::platform::windows::WindowBuilderExtWindows;
+
+    if let Some(v) = options.skip_taskbar {
+        builder = builder.with_skip_taskbar(v);
+    }
+    // Handles are checked to be nonzero and exclusive by `WindowOptions::validate`
+    if let Some(v) = options.owner_window {
+        builder = builder.with_owner_window(v);
+    }
+    if let Some(v) = options.parent_window {
+        builder = builder.with_parent_window(v);
+    }
+    if let Some(v) = &options.taskbar_icon {
+        builder = builder.with_taskbar_icon(Some(v.to_icon()?));
+    }
+    Ok(builder)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn windows_options(
+    builder: WindowBuilder,
+    options: &WindowOptions,
+) -> anyhow::Result<WindowBuilder> {
+    warn_ignored(
+        "Windows",
+        &[
+            (
+                "skipTaskbar",
+                options.skip_taskbar.is_some() && !cfg!(target_os = "linux"),
+            ),
+            ("ownerWindow", options.owner_window.is_some()),
+            ("parentWindow", options.parent_window.is_some()),
+            ("taskbarIcon", options.taskbar_icon.is_some()),
+        ],
+    );
+    Ok(builder)
+}
+
+/// Applies the Linux-only window options.
+#[cfg(target_os = "linux")]
+fn linux_options(mut builder: WindowBuilder, options: &WindowOptions) -> WindowBuilder {
+    use tao::platform::unix::WindowBuilderExtUnix;
+
+    if let Some(v) = options.skip_taskbar {
+        builder = builder.with_skip_taskbar(v);
+    }
+    if let Some(v) = options.double_buffered {
+        builder = builder.with_double_buffered(v);
+    }
+    if options.webkit_transparency_workaround.unwrap_or(false) {
+        builder = builder.with_transparent_draw(true);
+    } else if let Some(v) = options.transparent_draw {
+        builder = builder.with_transparent_draw(v);
+    }
+    builder
+}
+
+#[cfg(not(target_os = "linux"))]
+fn linux_options(builder: WindowBuilder, options: &WindowOptions) -> WindowBuilder {
+    warn_ignored(
+        "Linux",
+        &[
+            ("appId", options.app_id.is_some()),
+            ("doubleBuffered", options.double_buffered.is_some()),
+            ("transparentDraw", options.transparent_draw.is_some()),
+            (
+                "webkitTransparencyWorkaround",
+                options.webkit_transparency_workaround.is_some(),
+            ),
+        ],
+    );
+    builder
+}
 
 #[allow(dead_code)]
 pub struct FrameBuilder;
 
 impl FrameBuilder {
+    /// Builds the event loop, which on Linux also initializes GTK.
+    ///
+    /// ## Platform-specific
+    /// - Linux: Sets the GTK application id from `appId`. With
+    ///   `webkitTransparencyWorkaround`, sets `WEBKIT_DISABLE_DMABUF_RENDERER=1`
+    ///   unless already set, since the DMA-BUF renderer paints transparent
+    ///   webviews black on many drivers. Transparency also needs a compositing
+    ///   window manager.
+    pub fn event_loop(options: &WindowOptions) -> FrameEventLoop {
+        #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+        let mut builder = FrameEventLoopBuilder::with_user_event();
+        #[cfg(target_os = "linux")]
+        {
+            use tao::platform::unix::EventLoopBuilderExtUnix;
+
+            if let Some(id) = &options.app_id {
+                builder.with_app_id(id);
+            }
+            if options.webkit_transparency_workaround.unwrap_or(false)
+                && std::env::var_os("WEBKIT_DISABLE_DMABUF_RENDERER").is_none()
+            {
+                // Still single-threaded here: no runtime or webview exists yet
+                std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = options;
+        builder.build()
+    }
+
+    /// Builds the native window.
+    ///
+    /// Conflicting startup flags are reconciled first, see
+    /// [`pyorion_options::window::InitialState`].
+    ///
+    /// With `transparent` set, a missing `background_color` defaults to fully
+    /// transparent so the window does not render black. An opaque window that
+    /// resolves to the dark theme defaults to a dark background instead.
+    ///
+    /// ## Platform-specific
+    /// - Windows: Transparency needs `decorations = false` to take effect.
+    /// - macOS: Transparent webviews require the `transparent` feature of wry.
+    /// - Linux: Requires a compositing window manager.
     #[allow(dead_code)]
     pub fn build_window(
         target: &FrameWindowTarget,
         options: &WindowOptions,
     ) -> anyhow::Result<Window> {
         let mut builder = WindowBuilder::new();
+        let state = options.initial_state();
+        for note in &state.overrides {
+            tracing::warn!("{}", note);
+        }
 
         if let Some(v) = options.always_on_bottom {
             builder = builder.with_always_on_bottom(v);
@@ -27,7 +605,10 @@ impl FrameBuilder {
         if let Some(v) = options.always_on_top {
             builder = builder.with_always_on_top(v);
         }
-        if let Some(v) = options.background_color {
+        let transparent = options.transparent.unwrap_or(false);
+        if let Some(v) =
+            coherent_background(transparent, options.background_color, "backgroundColor")
+        {
             let [r, g, b, a] = [v.0, v.1, v.2, v.3];
             builder = builder.with_background_color((r, g, b, a));
         }
@@ -43,15 +624,9 @@ impl FrameBuilder {
         if let Some(v) = options.focusable {
             builder = builder.with_focusable(v);
         }
-        if let Some(v) = options.focused {
-            builder = builder.with_focused(v);
-        }
-        if let Some(v) = options.fullscreen {
-            if v {
-                builder = builder.with_fullscreen(Some(tao::window::Fullscreen::Borderless(None)));
-            } else {
-                builder = builder.with_fullscreen(None);
-            }
+        builder = builder.with_focused(state.focused);
+        if state.fullscreen {
+            builder = builder.with_fullscreen(Some(tao::window::Fullscreen::Borderless(None)));
         }
         if let Some(v) = options.clone().inner_size {
             builder = builder.with_inner_size::<Size>(v.into());
@@ -62,9 +637,7 @@ impl FrameBuilder {
         if let Some(v) = options.maximizable {
             builder = builder.with_maximizable(v);
         }
-        if let Some(v) = options.maximized {
-            builder = builder.with_maximized(v);
-        }
+        builder = builder.with_maximized(state.maximized);
         if let Some(v) = options.clone().min_inner_size {
             builder = builder.with_min_inner_size::<Size>(v.into());
         }
@@ -86,42 +659,118 @@ impl FrameBuilder {
         if let Some(v) = options.transparent {
             builder = builder.with_transparent(v);
         }
-        if let Some(v) = options.visible {
-            builder = builder.with_visible(v);
-        }
+        builder = builder.with_visible(state.visible);
         if let Some(v) = options.visible_on_all_workspaces {
             builder = builder.with_visible_on_all_workspaces(v);
         }
         if let Some(v) = &options.window_icon {
             builder = builder.with_window_icon(Some(v.to_icon()?));
         }
+        builder = macos_options(builder, options);
+        builder = windows_options(builder, options)?;
+        builder = linux_options(builder, options);
 
         let window = builder.build(target)?;
+        if let Some(v) = theme_background(&window, transparent, options.background_color) {
+            window.set_background_color(Some(v));
+        }
         Ok(window)
     }
+    /// Creates the web context holding cookies, localStorage and IndexedDB
+    /// in the [`data_directory`] for `options`.
+    pub fn web_context(options: &WindowOptions) -> anyhow::Result<wry::WebContext> {
+        let dir = data_directory(options, dirs::data_local_dir())?;
+        Ok(wry::WebContext::new(dir))
+    }
+
+    /// Resolves the settings [`FrameBuilder::build_webview`] applies for
+    /// `options` on this platform.
+    pub fn webview_config(
+        options: &WebViewOptions,
+        transparent_window: bool,
+        data_directory: Option<&std::path::Path>,
+    ) -> WebViewConfig {
+        let mobile = cfg!(any(target_os = "android", target_os = "ios"));
+        WebViewConfig {
+            label: options
+                .label
+                .clone()
+                .unwrap_or_else(|| DEFAULT_LABEL.to_string()),
+            devtools: options.devtools.unwrap_or(cfg!(debug_assertions))
+                && !cfg!(target_os = "android"),
+            autoplay: options.autoplay.unwrap_or(true),
+            clipboard: options.clipboard.unwrap_or(false),
+            incognito: options.incognito.unwrap_or(false) && !cfg!(target_os = "android"),
+            transparent: options.transparent.unwrap_or(transparent_window) && !mobile,
+            visible: options.visible.unwrap_or(true),
+            focused: options.focused.unwrap_or(true),
+            accept_first_mouse: options.accept_first_mouse.unwrap_or(false)
+                && cfg!(target_os = "macos"),
+            hotkeys_zoom: options.hotkeys_zoom.unwrap_or(false) && cfg!(target_os = "windows"),
+            file_drop: options.file_drop.unwrap_or(true),
+            user_agent: options.user_agent.clone(),
+            data_directory: data_directory.map(std::path::Path::to_path_buf),
+        }
+    }
+
+    /// Builds the webview hosted by `window`.
+    ///
+    /// Inside a transparent window the webview follows suit: `transparent`
+    /// and `background_color` default to transparent unless set explicitly.
+    /// In an opaque dark-themed window it defaults to a dark background.
+    /// Native events such as file drops are sent through `proxy`.
     #[allow(dead_code)]
     pub fn build_webview(
         window: &tao::window::Window,
+        web_context: &mut wry::WebContext,
         options: &WebViewOptions,
         sock_cfg: Option<crate::assets::WebSocketConfig>,
+        transparent_window: bool,
+        proxy: &FrameEventLoopProxy,
     ) -> anyhow::Result<wry::WebView> {
-        // websocket_config
-        let mut builder = wry::WebViewBuilder::new();
-
-        if let Some(conf) = sock_cfg {
-            let socket_conf = crate::assets::websocket_config(conf)?;
-            builder = builder
-                .with_initialization_script(socket_conf)
-                .with_initialization_script(crate::assets::_COMMAND_SCRIPT);
+        let tls = sock_cfg.as_ref().and_then(|cfg| {
+            let tls = cfg.tls()?.clone();
+            Some((tls, cfg.host()?))
+        });
+        let socket_script = sock_cfg.map(crate::assets::websocket_config).transpose()?;
+        let mut builder = wry::WebViewBuilder::new_with_web_context(web_context);
+        for script in initialization_scripts(options, socket_script) {
+            builder = builder.with_initialization_script(script.as_ref());
         }
         if let Some(label) = &options.label {
             builder = builder.with_id(label.as_str());
         } else {
-            builder = builder.with_id("root_webview");
+            builder = builder.with_id(DEFAULT_LABEL);
         }
-        let binding = &options.render_protocol;
-        let mut builder = render_protocol(builder, binding.clone());
-        if let Some(v) = options.transparent {
+        let asset_headers = header_map(options.asset_headers.as_ref())?;
+        let (builder, implied) = match &options.assets {
+            Some(assets) => (
+                crate::assets::protocol::register(
+                    builder,
+                    crate::assets::store::AssetStore::from_options(assets)?,
+                    assets
+                        .index
+                        .clone()
+                        .unwrap_or_else(|| crate::assets::store::DEFAULT_INDEX.to_string()),
+                    asset_headers,
+                ),
+                Some(StartPage::Url(crate::assets::protocol::app_url())),
+            ),
+            None => render_protocol(builder, options.render_protocol.clone(), asset_headers),
+        };
+        // An explicit `url` or `html` wins over the page the assets imply,
+        // while `app://` keeps serving them
+        let start = match (&options.url, &options.html) {
+            (Some(url), _) => Some(StartPage::Url(url.clone())),
+            (None, Some(html)) => Some(StartPage::Html(html.clone())),
+            (None, None) => implied,
+        };
+        let mut builder = match start {
+            Some(start) => start.load(builder),
+            None => builder,
+        };
+        let transparent = options.transparent.or(transparent_window.then_some(true));
+        if let Some(v) = transparent {
             builder = builder.with_transparent(v);
         }
         if let Some(v) = options.visible {
@@ -133,11 +782,31 @@ impl FrameBuilder {
         if let Some(v) = options.incognito {
             builder = builder.with_incognito(v);
         }
+        if let Some(proxy) = &options.proxy_config {
+            builder = builder.with_proxy_config(proxy.to_wry()?);
+        }
+        crate::window::navigation::set_allowlist(options.navigation_allowlist.clone());
+        crate::window::navigation::set_confirm_timeout(
+            options
+                .navigation_confirm_timeout_ms
+                .map(std::time::Duration::from_millis),
+        );
+        builder = builder
+            .with_navigation_handler(crate::window::navigation::navigation_handler(proxy.clone()))
+            .with_on_page_load_handler(crate::window::navigation::page_load_handler(proxy.clone()));
+        if options.file_drop.unwrap_or(true) {
+            builder = builder.with_drag_drop_handler(drag_drop_handler(proxy.clone()));
+        }
         if let Some(v) = &options.user_agent {
             builder = builder.with_user_agent(v);
         }
-        if let Some(v) = &options.initialization_script {
-            builder = builder.with_initialization_script(v);
+        if options.enable_drag_regions.unwrap_or(false) {
+            let proxy = proxy.clone();
+            builder = builder.with_ipc_handler(move |request| {
+                if request.body() == crate::assets::DRAG_MESSAGE {
+                    let _ = proxy.send_event(UserEvent::DragWindow);
+                }
+            });
         }
         if let Some(v) = options.accept_first_mouse {
             builder = builder.with_accept_first_mouse(v);
@@ -154,22 +823,27 @@ impl FrameBuilder {
         if let Some(v) = options.hotkeys_zoom {
             builder = builder.with_hotkeys_zoom(v);
         }
-        if let Some(v) = options.background_color {
+        if let Some(v) = coherent_background(
+            transparent.unwrap_or(false),
+            options.background_color,
+            "webview.backgroundColor",
+        )
+        .or_else(|| {
+            theme_background(
+                window,
+                transparent.unwrap_or(false),
+                options.background_color,
+            )
+        }) {
             let (r, g, b, a) = v;
             builder = builder.with_background_color((r, g, b, a));
         }
         if let Some(bounds) = options.clone().bounds {
             builder = builder.with_bounds(bounds.into());
         }
-        if let Some(map) = &options.headers {
-            let mut headers = wry::http::HeaderMap::new();
-            for (k, v) in map {
-                headers.insert(
-                    wry::http::HeaderName::from_bytes(k.as_bytes())?,
-                    wry::http::HeaderValue::from_str(v)?,
-                );
-            }
-            builder = builder.with_headers(headers);
+        // Sent with the initial navigation request
+        if options.headers.is_some() {
+            builder = builder.with_headers(header_map(options.headers.as_ref())?);
         }
 
         // macOS specific
@@ -178,11 +852,254 @@ impl FrameBuilder {
         }
 
         // Windows specific
-        if let Some(v) = options.transparent {
+        if let Some(v) = transparent {
             builder = builder.with_transparent(v);
         }
+        let insecure = tls
+            .as_ref()
+            .is_some_and(|(tls, _)| tls.insecure_skip_verify());
+        builder = webview2_options(builder, options, insecure);
 
-        let webview = builder.build(&window)?;
+        let webview = builder.build(window).map_err(WebViewError::from)?;
+        if let Some((tls, host)) = &tls {
+            trust_backend(&webview, tls, host)?;
+        }
         Ok(webview)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects what a subscriber writes.
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Runs `f` and returns its result with the warnings it logged.
+    fn warnings<T>(f: impl FnOnce() -> T) -> (T, String) {
+        let logs = Logs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::WARN)
+            .with_ansi(false)
+            .with_writer({
+                let logs = logs.clone();
+                move || logs.clone()
+            })
+            .finish();
+        let result = tracing::subscriber::with_default(subscriber, f);
+        let logged = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        (result, logged)
+    }
+
+    #[test]
+    fn transparent_background_defaults_to_clear() {
+        let (color, logged) = warnings(|| coherent_background(true, None, "backgroundColor"));
+        assert_eq!(color, Some((0, 0, 0, 0)));
+        assert!(logged.is_empty());
+    }
+
+    #[test]
+    fn transparent_background_keeps_a_translucent_color() {
+        let (color, logged) =
+            warnings(|| coherent_background(true, Some((10, 20, 30, 128)), "backgroundColor"));
+        assert_eq!(color, Some((10, 20, 30, 128)));
+        assert!(logged.is_empty());
+    }
+
+    #[test]
+    fn transparent_background_keeps_an_opaque_color_with_a_warning() {
+        let (color, logged) =
+            warnings(|| coherent_background(true, Some((10, 20, 30, 255)), "backgroundColor"));
+        assert_eq!(color, Some((10, 20, 30, 255)));
+        assert!(logged.contains("WARN"));
+        assert!(logged.contains("color is opaque"));
+        assert!(logged.contains("backgroundColor"));
+    }
+
+    #[test]
+    fn opaque_frame_passes_the_color_through() {
+        let (color, logged) = warnings(|| {
+            (
+                coherent_background(false, None, "backgroundColor"),
+                coherent_background(false, Some((10, 20, 30, 255)), "backgroundColor"),
+            )
+        });
+        assert_eq!(color, (None, Some((10, 20, 30, 255))));
+        assert!(logged.is_empty());
+    }
+
+    #[test]
+    fn transparent_webview_makes_the_window_transparent() {
+        let mut options = WindowOptions::default();
+        options.webview.transparent = Some(true);
+
+        let coherent = coherent_transparency(&options);
+        assert_eq!(coherent.transparent, Some(true));
+        let transparent = coherent.transparent.unwrap_or(false);
+        let config = FrameBuilder::webview_config(&coherent.webview, transparent, None);
+        assert!(config.transparent);
+
+        // A window left alone follows its webview into the config as well
+        options.webview.transparent = None;
+        options.transparent = Some(true);
+        let config = FrameBuilder::webview_config(&options.webview, true, None);
+        assert!(config.transparent);
+    }
+
+    #[test]
+    fn explicitly_opaque_window_stays_opaque() {
+        let mut options = WindowOptions::default();
+        options.webview.transparent = Some(true);
+        options.transparent = Some(false);
+
+        let (coherent, logged) = warnings(|| coherent_transparency(&options).into_owned());
+        assert_eq!(coherent.transparent, Some(false));
+        assert!(logged.contains("webview.transparent has no effect"));
+        // The webview still reports what it was asked for
+        let config = FrameBuilder::webview_config(&coherent.webview, false, None);
+        assert!(config.transparent);
+
+        options.webview.transparent = None;
+        let config = FrameBuilder::webview_config(&options.webview, false, None);
+        assert!(!config.transparent);
+    }
+
+    #[test]
+    fn webview_is_focused_unless_told_otherwise() {
+        let mut options = WebViewOptions::default();
+        assert!(FrameBuilder::webview_config(&options, false, None).focused);
+        options.focused = Some(false);
+        assert!(!FrameBuilder::webview_config(&options, false, None).focused);
+        options.focused = Some(true);
+        assert!(FrameBuilder::webview_config(&options, false, None).focused);
+    }
+
+    #[test]
+    fn user_scripts_run_after_the_builtin_ones() {
+        let options = WebViewOptions {
+            invoke_helper: Some(true),
+            enable_drag_regions: Some(true),
+            initialization_script: Some("first()".to_string()),
+            initialization_scripts: Some(vec!["second()".to_string(), "third()".to_string()]),
+            ..Default::default()
+        };
+
+        let scripts = initialization_scripts(&options, Some("socket()".to_string()));
+        assert_eq!(
+            scripts,
+            [
+                "socket()",
+                crate::assets::_COMMAND_SCRIPT,
+                crate::assets::DRAG_REGION_SCRIPT,
+                "first()",
+                "second()",
+                "third()",
+            ]
+        );
+    }
+
+    #[test]
+    fn command_script_needs_the_socket_script() {
+        let options = WebViewOptions {
+            initialization_script: Some("first()".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(initialization_scripts(&options, None), ["first()"]);
+    }
+
+    #[test]
+    fn command_script_is_opt_in() {
+        let options = WebViewOptions::default();
+        let scripts = initialization_scripts(&options, Some("socket()".to_string()));
+        assert_eq!(scripts, ["socket()"]);
+    }
+
+    fn options(app_id: Option<&str>, title: Option<&str>) -> WindowOptions {
+        WindowOptions {
+            app_id: app_id.map(str::to_string),
+            title: title.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_data_directory_is_per_app() {
+        let base = tempfile::tempdir().unwrap();
+        let first = data_directory(
+            &options(Some("org.example.First"), None),
+            Some(base.path().into()),
+        )
+        .unwrap()
+        .unwrap();
+        let second = data_directory(&options(None, Some("Second App")), Some(base.path().into()))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            first,
+            base.path()
+                .join("pyorion")
+                .join("org_example_First")
+                .join(DEFAULT_LABEL)
+        );
+        assert_eq!(
+            second,
+            base.path()
+                .join("pyorion")
+                .join("Second_App")
+                .join(DEFAULT_LABEL)
+        );
+        assert!(first.is_dir());
+        assert!(second.is_dir());
+    }
+
+    #[test]
+    fn explicit_data_directory_is_created() {
+        let base = tempfile::tempdir().unwrap();
+        let dir = base.path().join("nested").join("storage");
+        let mut options = options(None, None);
+        options.webview.data_directory = Some(dir.to_string_lossy().into_owned());
+
+        assert_eq!(data_directory(&options, None).unwrap(), Some(dir.clone()));
+        assert!(dir.is_dir());
+    }
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod macos_tests {
+    use super::*;
+    use pyorion_options::window::{Position, UnitType};
+
+    #[test]
+    #[ignore = "tao only creates an event loop on the macOS main thread, which libtest does not run tests on"]
+    fn builds_a_window_with_a_custom_title_bar() {
+        let options = WindowOptions {
+            titlebar_transparent: Some(true),
+            full_size_content_view: Some(true),
+            title_hidden: Some(true),
+            traffic_light_inset: Some(Position {
+                x: Some(12),
+                y: Some(16),
+                unit: UnitType::Logical,
+            }),
+            visible: Some(false),
+            ..Default::default()
+        };
+
+        let event_loop = FrameBuilder::event_loop(&options);
+        let window = FrameBuilder::build_window(&event_loop, &options).unwrap();
+        assert!(!window.is_visible());
+    }
+}
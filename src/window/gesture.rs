@@ -0,0 +1,87 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::time::{Duration, Instant};
+
+/// Throttles the event flood of a move or resize drag.
+///
+/// The first change is forwarded at once, later ones at most once per
+/// `interval`, always carrying the latest payload. Once no change arrived for
+/// `interval` the gesture counts as settled: a change still held back is
+/// forwarded, followed by the end event with the final payload.
+pub struct Gesture {
+    event: &'static str,
+    end_event: &'static str,
+    /// When the last event went out, `None` outside of a gesture.
+    last_emit: Option<Instant>,
+    last_change: Instant,
+    latest: serde_json::Value,
+    held: bool,
+}
+
+impl Gesture {
+    pub fn new(event: &'static str, end_event: &'static str) -> Self {
+        Self {
+            event,
+            end_event,
+            last_emit: None,
+            last_change: Instant::now(),
+            latest: serde_json::Value::Null,
+            held: false,
+        }
+    }
+
+    /// Records a change and returns the events due right away.
+    pub fn change(
+        &mut self,
+        payload: serde_json::Value,
+        interval: Duration,
+    ) -> Vec<(&'static str, serde_json::Value)> {
+        let now = Instant::now();
+        self.last_change = now;
+        self.latest = payload;
+        match self.last_emit {
+            Some(last) if now.duration_since(last) < interval => {
+                self.held = true;
+                Vec::new()
+            }
+            _ => {
+                self.last_emit = Some(now);
+                self.held = false;
+                vec![(self.event, self.latest.clone())]
+            }
+        }
+    }
+
+    /// Returns the held back and end events once the gesture settled.
+    pub fn settle(&mut self, interval: Duration) -> Vec<(&'static str, serde_json::Value)> {
+        let mut events = Vec::new();
+        let Some(last) = self.last_emit else {
+            return events;
+        };
+        let now = Instant::now();
+        if self.held && now.duration_since(last) >= interval {
+            self.last_emit = Some(now);
+            self.held = false;
+            events.push((self.event, self.latest.clone()));
+        }
+        if now.duration_since(self.last_change) >= interval {
+            self.last_emit = None;
+            self.held = false;
+            events.push((self.end_event, self.latest.clone()));
+        }
+        events
+    }
+
+    /// When [`Gesture::settle`] has something to do next, if ever.
+    pub fn deadline(&self, interval: Duration) -> Option<Instant> {
+        let last = self.last_emit?;
+        let settled = self.last_change + interval;
+        Some(if self.held {
+            settled.min(last + interval)
+        } else {
+            settled
+        })
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use tao::{
+    event::{ElementState, KeyEvent},
+    keyboard::{Key, KeyCode, ModifiersState},
+};
+
+/// Modifier keys held down, as sent with key events.
+pub fn modifiers(state: ModifiersState) -> serde_json::Value {
+    serde_json::json!({
+        "shift": state.shift_key(),
+        "control": state.control_key(),
+        "alt": state.alt_key(),
+        "super": state.super_key(),
+    })
+}
+
+/// Name of the physical key, following `KeyboardEvent.code` (`KeyA`, `Enter`).
+fn code(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Unidentified(_) => "Unidentified".to_string(),
+        code => format!("{:?}", code),
+    }
+}
+
+/// Meaning of the key, following `KeyboardEvent.key`: the produced character
+/// (`a`, `A`) or the name of a named key (`Enter`, `ArrowLeft`).
+fn key(key: &Key<'_>) -> String {
+    match key {
+        Key::Character(text) => text.to_string(),
+        Key::Unidentified(_) => "Unidentified".to_string(),
+        Key::Dead(Some(c)) => c.to_string(),
+        key => format!("{:?}", key),
+    }
+}
+
+/// Turns a key press or release into a `keyDown`/`keyUp` event.
+pub fn key_event(event: &KeyEvent, state: ModifiersState) -> (&'static str, serde_json::Value) {
+    let name = match event.state {
+        ElementState::Pressed => "keyDown",
+        _ => "keyUp",
+    };
+    let payload = serde_json::json!({
+        "code": code(&event.physical_key),
+        "key": key(&event.logical_key),
+        "text": event.text,
+        "location": format!("{:?}", event.location).to_lowercase(),
+        "repeat": event.repeat,
+        "modifiers": modifiers(state),
+    });
+    (name, payload)
+}
@@ -6,17 +6,56 @@ use pyorion_options::window::WindowOptions;
 use tao::window::{Window, WindowId};
 use wry::WebView;
 
-use crate::{utils::FrameWindowTarget, window::builder::FrameBuilder};
+use crate::{
+    utils::{FrameEventLoopProxy, FrameWindowTarget},
+    window::builder::FrameBuilder,
+};
 
 pub(crate) mod builder;
+pub(crate) mod gesture;
+pub(crate) mod keys;
+pub(crate) mod navigation;
+pub(crate) mod state;
+
+/// A freshly built window with its webview.
+pub struct Frame {
+    pub id: WindowId,
+    pub window: Window,
+    pub webview: WebView,
+    /// Must outlive `webview`.
+    pub web_context: wry::WebContext,
+    pub config: builder::WebViewConfig,
+}
 
 pub fn create_frame(
     target: &FrameWindowTarget,
     options: &WindowOptions,
     sock_cfg: Option<crate::assets::WebSocketConfig>,
-) -> anyhow::Result<(WindowId, Window, WebView)> {
+    proxy: &FrameEventLoopProxy,
+) -> anyhow::Result<Frame> {
+    if let Some(label) = &options.webview.label {
+        builder::check_label(label)?;
+    }
+    let options = &builder::coherent_transparency(options);
     let window = FrameBuilder::build_window(target, options)?;
     let id = window.id();
-    let webview = FrameBuilder::build_webview(&window, &options.webview, sock_cfg)?;
-    Ok((id, window, webview))
+    let transparent = options.transparent.unwrap_or(false);
+    let mut web_context = FrameBuilder::web_context(options)?;
+    let webview = FrameBuilder::build_webview(
+        &window,
+        &mut web_context,
+        &options.webview,
+        sock_cfg,
+        transparent,
+        proxy,
+    )?;
+    let config =
+        FrameBuilder::webview_config(&options.webview, transparent, web_context.data_directory());
+    Ok(Frame {
+        id,
+        window,
+        webview,
+        web_context,
+        config,
+    })
 }
@@ -0,0 +1,252 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::utils::{FrameEventLoopProxy, Milestone, UserEvent};
+
+/// URL prefixes the webview may navigate to; `None` allows everything.
+///
+/// The webview asks synchronously on the UI thread whether a navigation may
+/// proceed, and Python can only be reached through that same thread, so a
+/// per-navigation round trip would dead-lock. Python decides ahead of time
+/// by setting this list, or later through [`Decisions`].
+static ALLOWLIST: Lazy<RwLock<Option<Vec<String>>>> = Lazy::new(|| RwLock::new(None));
+
+static DECISIONS: Lazy<Mutex<Decisions>> = Lazy::new(Default::default);
+
+/// Navigations the allowlist rejected, held for Python to decide on.
+///
+/// With a confirm timeout set, such a navigation is cancelled right away
+/// and announced as `navigationRequested`. If Python approves it through
+/// `webview.resolveNavigation` before the timeout, or does not answer in
+/// time, the URL is loaded again and let through once.
+#[derive(Default)]
+struct Decisions {
+    timeout: Option<Duration>,
+    next_id: u64,
+    held: HashMap<u64, (String, Instant)>,
+    approved: Vec<String>,
+}
+
+impl Decisions {
+    /// Holds `url` and returns its id, or `None` when nothing is held.
+    fn hold(&mut self, url: &str) -> Option<(u64, Duration)> {
+        let timeout = self.timeout?;
+        self.next_id += 1;
+        self.held
+            .insert(self.next_id, (url.to_string(), Instant::now() + timeout));
+        Some((self.next_id, timeout))
+    }
+
+    /// Settles held navigation `id`, returning its URL; an allowed one is
+    /// let through when it is loaded again.
+    fn resolve(&mut self, id: u64, allow: bool) -> Option<String> {
+        let (url, _) = self.held.remove(&id)?;
+        if allow {
+            self.approved.push(url.clone());
+        }
+        Some(url)
+    }
+
+    /// Allows the held navigations that ran out of time and returns their URLs.
+    fn expire(&mut self, now: Instant) -> Vec<String> {
+        let expired: Vec<u64> = self
+            .held
+            .iter()
+            .filter(|(_, (_, deadline))| *deadline <= now)
+            .map(|(id, _)| *id)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|id| self.resolve(id, true))
+            .collect()
+    }
+
+    /// Consumes an approval for `url`, if there is one.
+    fn take_approval(&mut self, url: &str) -> bool {
+        // The webview may add a trailing slash to the URL it was given
+        let same = |approved: &String| approved.trim_end_matches('/') == url.trim_end_matches('/');
+        match self.approved.iter().position(same) {
+            Some(index) => {
+                self.approved.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.held.values().map(|(_, deadline)| *deadline).min()
+    }
+}
+
+/// Sets how long a navigation the allowlist rejects waits for Python;
+/// `None` blocks such navigations outright.
+pub fn set_confirm_timeout(timeout: Option<Duration>) {
+    if let Ok(mut decisions) = DECISIONS.lock() {
+        decisions.timeout = timeout;
+    }
+}
+
+/// Settles the held navigation `id` and returns its URL, which the caller
+/// loads again if `allow` is set. `None` if no such navigation is held.
+pub fn resolve(id: u64, allow: bool) -> Option<String> {
+    DECISIONS.lock().ok()?.resolve(id, allow)
+}
+
+/// URLs of held navigations that were not answered in time and are to be
+/// loaded again; the fallback allows them.
+pub fn expired() -> Vec<String> {
+    DECISIONS
+        .lock()
+        .map(|mut decisions| decisions.expire(Instant::now()))
+        .unwrap_or_default()
+}
+
+/// When the next held navigation runs out of time, if any is held.
+pub fn deadline() -> Option<Instant> {
+    DECISIONS.lock().ok()?.deadline()
+}
+
+/// Replaces the navigation allowlist.
+pub fn set_allowlist(prefixes: Option<Vec<String>>) {
+    if let Ok(mut allowlist) = ALLOWLIST.write() {
+        *allowlist = prefixes;
+    }
+}
+
+/// Whether `url` passes the allowlist. The app's own pages and
+/// `about:blank` are always allowed.
+fn allowed(url: &str) -> bool {
+    let Ok(allowlist) = ALLOWLIST.read() else {
+        return true;
+    };
+    let Some(prefixes) = allowlist.as_ref() else {
+        return true;
+    };
+    url == "about:blank"
+        || url.starts_with(&format!("{}://", crate::assets::protocol::APP_SCHEME))
+        || url.starts_with(&crate::assets::protocol::app_url())
+        || prefixes
+            .iter()
+            .any(|prefix| url.starts_with(prefix.as_str()))
+}
+
+fn emit(proxy: &FrameEventLoopProxy, name: &str, payload: serde_json::Value) {
+    let _ = proxy.send_event(UserEvent::Emit(name.to_string(), payload));
+}
+
+/// Emits `navigationStarted` for every navigation and `navigationBlocked`
+/// for those rejected by the allowlist, e.g. so Python can open external
+/// links in the system browser instead. With a confirm timeout set, the
+/// rejected ones are held and announced as `navigationRequested` instead.
+pub fn navigation_handler(proxy: FrameEventLoopProxy) -> impl Fn(String) -> bool {
+    move |url| {
+        let mut decisions = DECISIONS.lock().ok();
+        let allowed = decisions
+            .as_mut()
+            .is_some_and(|decisions| decisions.take_approval(&url))
+            || allowed(&url);
+        emit(
+            &proxy,
+            "navigationStarted",
+            serde_json::json!({ "url": url, "allowed": allowed }),
+        );
+        let held = match decisions.as_mut() {
+            Some(decisions) if !allowed => decisions.hold(&url),
+            _ => None,
+        };
+        if let Some((id, timeout)) = held {
+            emit(
+                &proxy,
+                "navigationRequested",
+                serde_json::json!({
+                    "id": id,
+                    "url": url,
+                    "timeoutMs": timeout.as_millis() as u64,
+                }),
+            );
+        } else if !allowed {
+            emit(
+                &proxy,
+                "navigationBlocked",
+                serde_json::json!({ "url": url }),
+            );
+        }
+        allowed
+    }
+}
+
+/// Emits `pageLoadStarted` and `pageLoadFinished` with the page URL.
+pub fn page_load_handler(proxy: FrameEventLoopProxy) -> impl Fn(wry::PageLoadEvent, String) {
+    move |event, url| {
+        let name = match event {
+            wry::PageLoadEvent::Started => "pageLoadStarted",
+            wry::PageLoadEvent::Finished => {
+                let _ = proxy.send_event(UserEvent::Milestone(Milestone::PageLoaded));
+                "pageLoadFinished"
+            }
+        };
+        emit(&proxy, name, serde_json::json!({ "url": url }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decisions(timeout_ms: u64) -> Decisions {
+        Decisions {
+            timeout: Some(Duration::from_millis(timeout_ms)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nothing_is_held_without_a_timeout() {
+        assert_eq!(Decisions::default().hold("https://example.com"), None);
+    }
+
+    #[test]
+    fn approval_lets_the_url_through_once() {
+        let mut decisions = decisions(1000);
+        let (id, _) = decisions.hold("https://example.com").unwrap();
+
+        assert_eq!(
+            decisions.resolve(id, true).as_deref(),
+            Some("https://example.com")
+        );
+        assert_eq!(decisions.resolve(id, true), None);
+        assert!(decisions.take_approval("https://example.com/"));
+        assert!(!decisions.take_approval("https://example.com/"));
+    }
+
+    #[test]
+    fn denial_keeps_the_url_blocked() {
+        let mut decisions = decisions(1000);
+        let (id, _) = decisions.hold("https://example.com").unwrap();
+
+        assert!(decisions.resolve(id, false).is_some());
+        assert!(!decisions.take_approval("https://example.com"));
+        assert_eq!(decisions.deadline(), None);
+    }
+
+    #[test]
+    fn unanswered_navigation_is_allowed_after_the_timeout() {
+        let mut decisions = decisions(50);
+        decisions.hold("https://example.com").unwrap();
+        let deadline = decisions.deadline().unwrap();
+
+        assert!(decisions
+            .expire(deadline - Duration::from_millis(1))
+            .is_empty());
+        assert_eq!(decisions.expire(deadline), vec!["https://example.com"]);
+        assert!(decisions.take_approval("https://example.com"));
+        assert_eq!(decisions.deadline(), None);
+    }
+}
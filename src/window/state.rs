@@ -0,0 +1,78 @@
+// Copyright 2025-2030 Ari Bermeki @ YellowSiC within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tao::window::Window;
+
+/// Display state of a window as reported by `windowStateChanged`.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowState {
+    Restored,
+    Minimized,
+    Maximized,
+    Fullscreen,
+}
+
+impl WindowState {
+    /// Reads the state of `window`; fullscreen wins over maximized, and a
+    /// minimized window counts as minimized whatever it returns to.
+    pub fn of(window: &Window) -> Self {
+        if window.is_minimized() {
+            WindowState::Minimized
+        } else if window.fullscreen().is_some() {
+            WindowState::Fullscreen
+        } else if window.is_maximized() {
+            WindowState::Maximized
+        } else {
+            WindowState::Restored
+        }
+    }
+}
+
+/// Watches for state changes without a dedicated tao event.
+///
+/// tao has no event for maximizing or minimizing, so every move, resize and
+/// focus change schedules a look at the window once events stood still for
+/// the interval; rapid transitions (e.g. restore then maximize) collapse into
+/// one event with the state that stuck.
+pub struct StateWatch {
+    last: WindowState,
+    due: Option<Instant>,
+}
+
+impl StateWatch {
+    pub fn new(initial: WindowState) -> Self {
+        Self {
+            last: initial,
+            due: None,
+        }
+    }
+
+    /// Notes a window event that may have changed the state.
+    pub fn touch(&mut self, interval: Duration) {
+        self.due = Some(Instant::now() + interval);
+    }
+
+    /// Once due, reads the state and returns the `windowStateChanged`
+    /// payload if it differs from the last one reported.
+    pub fn settle(&mut self, window: &Window) -> Option<serde_json::Value> {
+        if self.due? > Instant::now() {
+            return None;
+        }
+        self.due = None;
+        let state = WindowState::of(window);
+        if state == self.last {
+            return None;
+        }
+        let previous = std::mem::replace(&mut self.last, state);
+        Some(serde_json::json!({ "state": state, "previous": previous }))
+    }
+
+    /// When [`StateWatch::settle`] has something to do next, if ever.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.due
+    }
+}
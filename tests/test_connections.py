@@ -0,0 +1,38 @@
+import asyncio
+
+import pytest
+
+from pyorion.runtime.connections import create_websocket_server
+
+
+async def _bind(url: str) -> int:
+    bound: asyncio.Future[int] = asyncio.get_running_loop().create_future()
+    server = asyncio.create_task(create_websocket_server(url, bound))
+    try:
+        return await asyncio.wait_for(bound, timeout=5)
+    finally:
+        server.cancel()
+
+
+def test_port_zero_reports_bound_port() -> None:
+    assert asyncio.run(_bind("ws://127.0.0.1:0")) > 0
+
+
+@pytest.mark.parametrize("host", ["127.0.0.1", "localhost", "[::1]"])
+def test_hosts(host: str) -> None:
+    assert asyncio.run(_bind(f"ws://{host}:0/ws")) > 0
+
+
+def test_unbracketed_ipv6_is_rejected() -> None:
+    with pytest.raises(ValueError):
+        asyncio.run(_bind("ws://::1:8765/ws"))
+
+
+@pytest.mark.parametrize("url", ["ws://127.0.0.1:0", "127.0.0.1:0"])
+def test_ws_scheme_or_bare_host(url: str) -> None:
+    assert asyncio.run(_bind(url)) > 0
+
+
+def test_http_scheme_is_rejected() -> None:
+    with pytest.raises(ValueError, match="ws://"):
+        asyncio.run(_bind("http://127.0.0.1:0"))
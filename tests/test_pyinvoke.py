@@ -0,0 +1,15 @@
+import asyncio
+
+from pyorion.pyinvoke import dispatch_event, listen
+
+
+def test_listeners_filter_by_label() -> None:
+    seen: list[tuple[str, object]] = []
+
+    listen("labelTest")(lambda payload: seen.append(("any", payload)))
+    listen("labelTest", label="main")(lambda payload: seen.append(("main", payload)))
+
+    asyncio.run(dispatch_event("labelTest", 1, "main"))
+    asyncio.run(dispatch_event("labelTest", 2, "other"))
+
+    assert seen == [("any", 1), ("main", 1), ("any", 2)]
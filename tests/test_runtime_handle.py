@@ -0,0 +1,36 @@
+import asyncio
+from typing import Any
+
+import pytest
+
+from pyorion.runtime import runtime_handle
+from pyorion.runtime.runtime_handle import (
+    RuntimeHandle,
+    activate_runtime,
+    send_loop_event,
+)
+
+
+def test_runtimes_keep_their_own_settings(monkeypatch: pytest.MonkeyPatch) -> None:
+    sent: list[tuple[str, str | None, str]] = []
+
+    async def fake_send(
+        name: str, message: str, token: str | None, codec: str
+    ) -> list[Any]:
+        sent.append((name, token, codec))
+        return []
+
+    monkeypatch.setattr(runtime_handle, "send_event_over_platform", fake_send)
+
+    async def launch(runtime: RuntimeHandle) -> None:
+        activate_runtime(runtime)
+        await asyncio.sleep(0)
+        await send_loop_event([0, "app.version", []])
+
+    async def main() -> None:
+        first = RuntimeHandle(pipe_name="pipe_a", auth_token="a", codec="json")
+        second = RuntimeHandle(pipe_name="pipe_b", auth_token="b", codec="msgpack")
+        await asyncio.gather(launch(first), launch(second))
+
+    asyncio.run(main())
+    assert sorted(sent) == [("pipe_a", "a", "json"), ("pipe_b", "b", "msgpack")]
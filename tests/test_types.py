@@ -0,0 +1,68 @@
+import pytest
+from pydantic import ValidationError
+
+from pyorion.setup.types import (
+    ClipboardSetImageResult,
+    TlsConfig,
+    WebSocketConfig,
+    WebViewOptions,
+)
+
+
+def test_global_name_is_serialized() -> None:
+    cfg = WebSocketConfig(url="ws://127.0.0.1:8765", global_name="appSocket")
+    assert '"globalName":"appSocket"' in cfg.model_dump_json(by_alias=True)
+
+
+@pytest.mark.parametrize("name", ["", "1socket", "my-socket", "window.socket"])
+def test_illegal_global_name_is_rejected(name: str) -> None:
+    with pytest.raises(ValidationError):
+        WebSocketConfig(url="ws://127.0.0.1:8765", global_name=name)
+
+
+def test_set_image_failure_is_parsed() -> None:
+    result = ClipboardSetImageResult.model_validate(
+        {"ok": False, "error": "dimensionMismatch", "message": "2x2 RGBA needs 16 bytes, got 3"}
+    )
+    assert not result.ok
+    assert result.error == "dimensionMismatch"
+
+
+def test_unknown_set_image_error_is_rejected() -> None:
+    with pytest.raises(ValidationError):
+        ClipboardSetImageResult.model_validate({"ok": False, "error": "other"})
+
+
+@pytest.mark.parametrize(
+    ("field", "value"),
+    [
+        ("url", "https://example.com"),
+        ("url", "app://localhost/settings.html"),
+        ("html", "<html><body>hi</body></html>"),
+    ],
+)
+def test_start_page_is_serialized(field: str, value: str) -> None:
+    options = WebViewOptions(**{field: value})
+    assert options.model_dump(by_alias=True, exclude_none=True)[field] == value
+
+
+def test_url_and_html_are_exclusive() -> None:
+    with pytest.raises(ValidationError):
+        WebViewOptions(url="https://example.com", html="<html></html>")
+
+
+def test_tls_ca_cert_is_serialized() -> None:
+    tls = TlsConfig.model_validate({"enabled": True, "caCert": "backend.pem"})
+    assert tls.model_dump(by_alias=True, mode="json")["caCert"] == "backend.pem"
+
+
+def test_insecure_skip_verify_needs_the_opt_in() -> None:
+    with pytest.raises(ValidationError):
+        TlsConfig(enabled=True, insecure_skip_verify=True)
+    tls = TlsConfig(enabled=True, insecure_skip_verify=True, allow_insecure=True)
+    assert tls.model_dump(by_alias=True)["insecureSkipVerify"]
+
+
+def test_unknown_tls_settings_are_rejected() -> None:
+    with pytest.raises(ValidationError):
+        TlsConfig.model_validate({"enabled": True, "verify": False})